@@ -0,0 +1,57 @@
+use bn254::{FixedBaseTable, Fr, G1Affine, G1Projective, G2Affine, G2Projective, Gt};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand_core::OsRng;
+
+fn scalar_mul(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let k = Fr::random(&mut rng);
+    let g1 = G1Projective::from(G1Affine::generator());
+    let g2 = G2Projective::from(G2Affine::generator());
+    let gt = Gt::generator();
+
+    let mut group = c.benchmark_group("scalar_mul");
+    group.bench_function("g1", |b| b.iter(|| black_box(g1) * black_box(k)));
+    group.bench_function("g2", |b| b.iter(|| black_box(g2) * black_box(k)));
+    group.bench_function("gt", |b| b.iter(|| black_box(gt) * black_box(k)));
+    group.finish();
+}
+
+fn fixed_base_mul(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let k = Fr::random(&mut rng);
+    let g1 = FixedBaseTable::from(G1Affine::generator());
+    let g2 = FixedBaseTable::from(G2Affine::generator());
+
+    let mut group = c.benchmark_group("fixed_base_mul");
+    group.bench_function("g1", |b| b.iter(|| &g1 * black_box(k)));
+    group.bench_function("g2", |b| b.iter(|| &g2 * black_box(k)));
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let points = (0..1000)
+        .map(|_| G1Projective::generator() * Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+    let mut affine = vec![G1Affine::identity(); points.len()];
+    G1Projective::batch_normalize(&points, &mut affine);
+
+    let mut group = c.benchmark_group("batch_1000");
+    group.bench_function("g1_normalize", |b| {
+        b.iter(|| G1Projective::batch_normalize(black_box(&points), &mut affine))
+    });
+    group.bench_function("g1_sum_affine", |b| {
+        b.iter(|| G1Affine::sum(black_box(&affine)))
+    });
+    group.bench_function("g1_sum_projective", |b| {
+        b.iter(|| {
+            black_box(&points)
+                .iter()
+                .fold(G1Projective::identity(), |acc, p| acc + *p)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scalar_mul, fixed_base_mul, batch);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use bn254::{Fq, Fq12, Fq2, Fq6};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand_core::OsRng;
+
+fn fq(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let a = Fq::random(&mut rng);
+    let b = Fq::random(&mut rng);
+
+    let mut group = c.benchmark_group("fq");
+    group.bench_function("mul", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("square", |bench| bench.iter(|| black_box(a).square()));
+    group.bench_function("invert", |bench| bench.iter(|| black_box(a).invert()));
+    group.finish();
+}
+
+fn fq2(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let a = Fq2::random(&mut rng);
+    let b = Fq2::random(&mut rng);
+
+    let mut group = c.benchmark_group("fq2");
+    group.bench_function("mul", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("square", |bench| bench.iter(|| black_box(a).square()));
+    group.finish();
+}
+
+fn fq6(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let a = Fq6::random(&mut rng);
+    let b = Fq6::random(&mut rng);
+
+    let mut group = c.benchmark_group("fq6");
+    group.bench_function("mul", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("square", |bench| bench.iter(|| black_box(a).square()));
+    group.finish();
+}
+
+fn fq12(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let a = Fq12::random(&mut rng);
+    let b = Fq12::random(&mut rng);
+
+    let mut group = c.benchmark_group("fq12");
+    group.bench_function("mul", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("square", |bench| bench.iter(|| black_box(a).square()));
+    group.finish();
+}
+
+criterion_group!(benches, fq, fq2, fq6, fq12);
+criterion_main!(benches);
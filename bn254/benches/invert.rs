@@ -0,0 +1,63 @@
+use bn254::{Fq, Fr, Gt};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rand_core::OsRng;
+
+/// r - 2, the exponent of the previous Fermat inversion path
+const R_MINUS_TWO: [u64; 4] = [
+    0x43e1f593efffffff,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// q - 2, the exponent of the previous Fermat inversion path
+const Q_MINUS_TWO: [u64; 4] = [
+    0x3c208c16d87cfd45,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+fn invert(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let a = Fr::random(&mut rng);
+    let q = Fq::random(&mut rng);
+    let f = Gt::generator().0;
+
+    let mut group = c.benchmark_group("invert");
+    group.bench_function("fr_safegcd", |b| b.iter(|| black_box(a).invert()));
+    group.bench_function("fr_fermat", |b| b.iter(|| black_box(a).pow(R_MINUS_TWO)));
+    group.bench_function("fq_safegcd", |b| b.iter(|| black_box(q).invert()));
+    group.bench_function("fq_fermat", |b| b.iter(|| black_box(q).pow(Q_MINUS_TWO)));
+    group.bench_function("fq12", |b| b.iter(|| black_box(f).invert()));
+    group.finish();
+}
+
+fn batch_invert(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let elems = (0..1024).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("batch_invert_1024");
+    group.bench_function("batch", |b| {
+        b.iter_batched_ref(
+            || elems.clone(),
+            |elems| Fr::batch_invert(elems),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("one_by_one", |b| {
+        b.iter_batched_ref(
+            || elems.clone(),
+            |elems| {
+                for elem in elems.iter_mut() {
+                    *elem = elem.invert().unwrap();
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, invert, batch_invert);
+criterion_main!(benches);
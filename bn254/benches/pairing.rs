@@ -0,0 +1,51 @@
+use bn254::{AteParing, Fr, G1Affine, G2Affine, G2PairingAffine};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand_core::OsRng;
+
+fn prepare(c: &mut Criterion) {
+    let g2 = G2Affine::generator();
+
+    c.bench_function("g2_pairing_affine_from", |b| {
+        b.iter(|| G2PairingAffine::from(black_box(g2)))
+    });
+}
+
+fn multi_miller_loop(c: &mut Criterion) {
+    let mut rng = OsRng;
+    let pairs = (0..64)
+        .map(|_| {
+            let g1 = G1Affine::from(G1Affine::generator() * Fr::random(&mut rng));
+            let g2 = G2Affine::from(G2Affine::generator() * Fr::random(&mut rng));
+            (g1, G2PairingAffine::from(g2))
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("multi_miller_loop");
+    for n in [1, 2, 4, 8, 16, 32, 64] {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &pairs[..n], |b, pairs| {
+            b.iter(|| AteParing::multi_miller_loop(black_box(pairs)))
+        });
+    }
+    group.finish();
+}
+
+fn final_exp(c: &mut Criterion) {
+    let g1 = G1Affine::generator();
+    let g2 = G2PairingAffine::from(G2Affine::generator());
+    let f = AteParing::multi_miller_loop(&[(g1, g2)]);
+
+    c.bench_function("final_exp", |b| b.iter(|| black_box(f).final_exp()));
+}
+
+fn pairing(c: &mut Criterion) {
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+
+    c.bench_function("pairing", |b| {
+        b.iter(|| AteParing::pairing(black_box(g1), black_box(g2)))
+    });
+}
+
+criterion_group!(benches, prepare, multi_miller_loop, final_exp, pairing);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+//! Conversions to and from the arkworks `ark-bn254` types, for migrating
+//! code a piece at a time and comparing the two implementations.
+//!
+//! Both sides use the same extension tower, so the fields convert limb for
+//! limb through the canonical integers. Every element of this crate is a
+//! valid arkworks element, while arkworks can hold points off the curve or
+//! outside the subgroup and target group elements that no pairing
+//! produces, so those directions are `TryFrom`.
+//!
+//! `Gt` converts exactly, but the pairings themselves differ: the arkworks
+//! final exponentiation raises to an extra 2x(6x^2 + 3x + 1), so its
+//! pairing is this crate's raised to that power.
+use ark_ec::pairing::PairingOutput;
+use ark_ff::{BigInt, PrimeField};
+use core::fmt;
+
+use crate::fq::Fq;
+use crate::fq12::Fq12;
+use crate::fq2::Fq2;
+use crate::fq6::Fq6;
+use crate::fr::Fr;
+use crate::g1::G1Affine;
+use crate::g2::G2Affine;
+use crate::gt::Gt;
+
+/// Errors converting arkworks values that are not valid group elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArkError {
+    /// The point does not satisfy the curve equation.
+    NotOnCurve,
+    /// The point or target group element is outside the order r subgroup.
+    NotInSubgroup,
+}
+
+impl fmt::Display for ArkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotOnCurve => write!(f, "point is not on the curve"),
+            Self::NotInSubgroup => write!(f, "element is not in the order r subgroup"),
+        }
+    }
+}
+
+impl From<Fq> for ark_bn254::Fq {
+    fn from(val: Fq) -> Self {
+        Self::new(BigInt(val.to_raw()))
+    }
+}
+
+impl From<ark_bn254::Fq> for Fq {
+    fn from(val: ark_bn254::Fq) -> Self {
+        Fq::to_mont_form(val.into_bigint().0)
+    }
+}
+
+impl From<Fr> for ark_bn254::Fr {
+    fn from(val: Fr) -> Self {
+        Self::new(BigInt(val.to_raw()))
+    }
+}
+
+impl From<ark_bn254::Fr> for Fr {
+    fn from(val: ark_bn254::Fr) -> Self {
+        Fr::to_mont_form(val.into_bigint().0)
+    }
+}
+
+impl From<Fq2> for ark_bn254::Fq2 {
+    fn from(val: Fq2) -> Self {
+        Self::new(val.0[0].into(), val.0[1].into())
+    }
+}
+
+impl From<ark_bn254::Fq2> for Fq2 {
+    fn from(val: ark_bn254::Fq2) -> Self {
+        Fq2([val.c0.into(), val.c1.into()])
+    }
+}
+
+impl From<Fq6> for ark_bn254::Fq6 {
+    fn from(val: Fq6) -> Self {
+        Self::new(val.0[0].into(), val.0[1].into(), val.0[2].into())
+    }
+}
+
+impl From<ark_bn254::Fq6> for Fq6 {
+    fn from(val: ark_bn254::Fq6) -> Self {
+        Fq6([val.c0.into(), val.c1.into(), val.c2.into()])
+    }
+}
+
+impl From<Fq12> for ark_bn254::Fq12 {
+    fn from(val: Fq12) -> Self {
+        Self::new(val.0[0].into(), val.0[1].into())
+    }
+}
+
+impl From<ark_bn254::Fq12> for Fq12 {
+    fn from(val: ark_bn254::Fq12) -> Self {
+        Fq12([val.c0.into(), val.c1.into()])
+    }
+}
+
+impl From<G1Affine> for ark_bn254::G1Affine {
+    fn from(p: G1Affine) -> Self {
+        if p.is_identity() {
+            return Self::identity();
+        }
+        Self::new_unchecked(p.x.into(), p.y.into())
+    }
+}
+
+impl TryFrom<ark_bn254::G1Affine> for G1Affine {
+    type Error = ArkError;
+
+    fn try_from(p: ark_bn254::G1Affine) -> Result<Self, ArkError> {
+        if p.infinity {
+            return Ok(G1Affine::identity());
+        }
+        if !p.is_on_curve() {
+            return Err(ArkError::NotOnCurve);
+        }
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&Fq::from(p.x).to_bytes_be());
+        bytes[32..].copy_from_slice(&Fq::from(p.y).to_bytes_be());
+        Option::from(G1Affine::from_uncompressed(&bytes)).ok_or(ArkError::NotOnCurve)
+    }
+}
+
+impl From<G2Affine> for ark_bn254::G2Affine {
+    fn from(p: G2Affine) -> Self {
+        if p.is_identity() {
+            return Self::identity();
+        }
+        Self::new_unchecked(p.x.into(), p.y.into())
+    }
+}
+
+impl TryFrom<ark_bn254::G2Affine> for G2Affine {
+    type Error = ArkError;
+
+    fn try_from(p: ark_bn254::G2Affine) -> Result<Self, ArkError> {
+        if p.infinity {
+            return Ok(G2Affine::identity());
+        }
+        if !p.is_on_curve() {
+            return Err(ArkError::NotOnCurve);
+        }
+        let mut bytes = [0; 128];
+        bytes[..64].copy_from_slice(&Fq2::from(p.x).to_bytes_be());
+        bytes[64..].copy_from_slice(&Fq2::from(p.y).to_bytes_be());
+        Option::from(G2Affine::from_uncompressed(&bytes)).ok_or(ArkError::NotInSubgroup)
+    }
+}
+
+impl From<Gt> for PairingOutput<ark_bn254::Bn254> {
+    fn from(gt: Gt) -> Self {
+        Self(gt.0.into())
+    }
+}
+
+impl TryFrom<PairingOutput<ark_bn254::Bn254>> for Gt {
+    type Error = ArkError;
+
+    fn try_from(gt: PairingOutput<ark_bn254::Bn254>) -> Result<Self, ArkError> {
+        let gt = Gt(gt.0.into());
+        if bool::from(gt.is_in_subgroup()) {
+            Ok(gt)
+        } else {
+            Err(ArkError::NotInSubgroup)
+        }
+    }
+}
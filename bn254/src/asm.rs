@@ -0,0 +1,311 @@
+//! Montgomery multiplication with the BMI2 `mulx` and ADX `adcx`/`adox`
+//! instructions, which keep two independent carry chains in flight.
+//!
+//! The reduction is interleaved with the multiplication (CIOS) and skips
+//! the carry word, which is sound while the top limb of the modulus is
+//! below `(2^64 - 1) / 2 - 1`. Both the base and the scalar field qualify.
+//! The result is in `[0, 2p)` until the final conditional subtraction.
+//!
+//! Squaring computes each cross product once, doubles them and adds the
+//! squares of the limbs, then reduces the low half of the 512-bit square
+//! in four rounds and adds the high half, 10 `mulx` and 16 for the
+//! reduction instead of 32.
+use core::arch::asm;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::math::{adb, adc, sba, sbb};
+
+const UNKNOWN: u8 = 0;
+const MISSING: u8 = 1;
+const PRESENT: u8 = 2;
+
+static ADX: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Whether the CPU supports both BMI2 and ADX. `cpuid` is only executed
+/// once, the answer is cached for every later call.
+#[inline(always)]
+pub(crate) fn has_adx() -> bool {
+    match ADX.load(Ordering::Relaxed) {
+        UNKNOWN => detect(),
+        state => state == PRESENT,
+    }
+}
+
+#[cold]
+fn detect() -> bool {
+    use core::arch::x86_64::{__cpuid_count, __get_cpuid_max};
+
+    // leaf 7 reports BMI2 in bit 8 and ADX in bit 19 of ebx
+    #[allow(unused_unsafe)]
+    let present = unsafe {
+        __get_cpuid_max(0).0 >= 7 && {
+            let ebx = __cpuid_count(7, 0).ebx;
+            ebx & (1 << 8) != 0 && ebx & (1 << 19) != 0
+        }
+    };
+    ADX.store(if present { PRESENT } else { MISSING }, Ordering::Relaxed);
+    present
+}
+
+/// `t += a * b[i]`, the carries of the low and high halves run on the
+/// OF and CF chains respectively and meet in the spare word.
+macro_rules! mul_word {
+    (first) => {
+        concat!(
+            "mov rdx, qword ptr [{b}]\n",
+            "xor eax, eax\n",
+            "mulx {t1}, {t0}, qword ptr [{a}]\n",
+            "mulx {t2}, rax, qword ptr [{a} + 8]\n",
+            "adox {t1}, rax\n",
+            "mulx {t3}, rax, qword ptr [{a} + 16]\n",
+            "adox {t2}, rax\n",
+            "mulx {hi}, rax, qword ptr [{a} + 24]\n",
+            "adox {t3}, rax\n",
+            "mov eax, 0\n",
+            "adox {hi}, rax\n",
+        )
+    };
+    ($offset:literal) => {
+        concat!(
+            "mov rdx, qword ptr [{b} + ",
+            $offset,
+            "]\n",
+            "xor eax, eax\n",
+            "mulx {spare}, rax, qword ptr [{a}]\n",
+            "adox {t0}, rax\n",
+            "adcx {t1}, {spare}\n",
+            "mulx {spare}, rax, qword ptr [{a} + 8]\n",
+            "adox {t1}, rax\n",
+            "adcx {t2}, {spare}\n",
+            "mulx {spare}, rax, qword ptr [{a} + 16]\n",
+            "adox {t2}, rax\n",
+            "adcx {t3}, {spare}\n",
+            "mulx {hi}, rax, qword ptr [{a} + 24]\n",
+            "adox {t3}, rax\n",
+            "mov eax, 0\n",
+            "adcx {hi}, rax\n",
+            "adox {hi}, rax\n",
+        )
+    };
+}
+
+/// `t = (t + m * p) / 2^64` with `m = t0 * inv`, folding `hi` back in.
+/// The `low` form reduces a value below `2^256`, which has no `hi`, and
+/// uses the register of the dead pointer `a` as scratch.
+macro_rules! reduce_word {
+    (low) => {
+        concat!(
+            "mov rdx, {inv}\n",
+            "imul rdx, {t0}\n",
+            "xor eax, eax\n",
+            "mulx {a}, rax, qword ptr [{p}]\n",
+            "adcx rax, {t0}\n",
+            "mov {t0}, {a}\n",
+            "adcx {t0}, {t1}\n",
+            "mulx {t1}, rax, qword ptr [{p} + 8]\n",
+            "adox {t0}, rax\n",
+            "adcx {t1}, {t2}\n",
+            "mulx {t2}, rax, qword ptr [{p} + 16]\n",
+            "adox {t1}, rax\n",
+            "adcx {t2}, {t3}\n",
+            "mulx {t3}, rax, qword ptr [{p} + 24]\n",
+            "adox {t2}, rax\n",
+            "mov eax, 0\n",
+            "adcx {t3}, rax\n",
+            "adox {t3}, rax\n",
+        )
+    };
+    () => {
+        concat!(
+            "mov rdx, {inv}\n",
+            "imul rdx, {t0}\n",
+            "xor eax, eax\n",
+            "mulx {spare}, rax, qword ptr [{p}]\n",
+            "adcx rax, {t0}\n",
+            "mov {t0}, {spare}\n",
+            "adcx {t0}, {t1}\n",
+            "mulx {t1}, rax, qword ptr [{p} + 8]\n",
+            "adox {t0}, rax\n",
+            "adcx {t1}, {t2}\n",
+            "mulx {t2}, rax, qword ptr [{p} + 16]\n",
+            "adox {t1}, rax\n",
+            "adcx {t2}, {t3}\n",
+            "mulx {t3}, rax, qword ptr [{p} + 24]\n",
+            "adox {t2}, rax\n",
+            "mov eax, 0\n",
+            "adcx {t3}, rax\n",
+            "adox {t3}, {hi}\n",
+        )
+    };
+}
+
+/// # Safety
+///
+/// The CPU must support BMI2 and ADX.
+#[target_feature(enable = "bmi2,adx")]
+pub(crate) unsafe fn mul_adx(a: [u64; 4], b: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    let (t0, t1, t2, t3): (u64, u64, u64, u64);
+    asm!(
+        mul_word!(first),
+        reduce_word!(),
+        mul_word!("8"),
+        reduce_word!(),
+        mul_word!("16"),
+        reduce_word!(),
+        mul_word!("24"),
+        reduce_word!(),
+        a = in(reg) a.as_ptr(),
+        b = in(reg) b.as_ptr(),
+        p = in(reg) p.as_ptr(),
+        inv = in(reg) inv,
+        t0 = out(reg) t0,
+        t1 = out(reg) t1,
+        t2 = out(reg) t2,
+        t3 = out(reg) t3,
+        hi = out(reg) _,
+        spare = out(reg) _,
+        out("rax") _,
+        out("rdx") _,
+        options(pure, readonly, nostack),
+    );
+    reduce_once([t0, t1, t2, t3], p)
+}
+
+/// # Safety
+///
+/// The CPU must support BMI2 and ADX.
+#[target_feature(enable = "bmi2,adx")]
+pub(crate) unsafe fn square_adx(a: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    let (t0, t1, t2, t3): (u64, u64, u64, u64);
+    asm!(
+        // the cross products a_i * a_j for i < j into t1..t6, with t0 as
+        // scratch until the squares fill it
+        "mov rdx, qword ptr [{a}]",
+        "mulx {t2}, {t1}, qword ptr [{a} + 8]",
+        "mulx {t3}, rax, qword ptr [{a} + 16]",
+        "add {t2}, rax",
+        "mulx {t4}, rax, qword ptr [{a} + 24]",
+        "adc {t3}, rax",
+        "adc {t4}, 0",
+        "mov rdx, qword ptr [{a} + 8]",
+        "xor eax, eax",
+        "mulx {t0}, rax, qword ptr [{a} + 16]",
+        "adox {t3}, rax",
+        "adcx {t4}, {t0}",
+        "mulx {t5}, rax, qword ptr [{a} + 24]",
+        "adox {t4}, rax",
+        "mov eax, 0",
+        "adcx {t5}, rax",
+        "adox {t5}, rax",
+        "mov rdx, qword ptr [{a} + 16]",
+        "mulx {t6}, rax, qword ptr [{a} + 24]",
+        "add {t5}, rax",
+        "adc {t6}, 0",
+        // doubled
+        "xor {t7:e}, {t7:e}",
+        "add {t1}, {t1}",
+        "adc {t2}, {t2}",
+        "adc {t3}, {t3}",
+        "adc {t4}, {t4}",
+        "adc {t5}, {t5}",
+        "adc {t6}, {t6}",
+        "adc {t7}, 0",
+        // plus the squares a_i^2 at limb 2i
+        "mov rdx, qword ptr [{a}]",
+        "mulx rax, {t0}, rdx",
+        "add {t1}, rax",
+        "mov rdx, qword ptr [{a} + 8]",
+        "mulx rdx, rax, rdx",
+        "adc {t2}, rax",
+        "adc {t3}, rdx",
+        "mov rdx, qword ptr [{a} + 16]",
+        "mulx rdx, rax, rdx",
+        "adc {t4}, rax",
+        "adc {t5}, rdx",
+        "mov rdx, qword ptr [{a} + 24]",
+        "mulx rdx, rax, rdx",
+        "adc {t6}, rax",
+        "adc {t7}, rdx",
+        // the low half times 2^-256, in [0, p], plus the high half, which
+        // is below p / 4
+        reduce_word!(low),
+        reduce_word!(low),
+        reduce_word!(low),
+        reduce_word!(low),
+        "add {t0}, {t4}",
+        "adc {t1}, {t5}",
+        "adc {t2}, {t6}",
+        "adc {t3}, {t7}",
+        a = inout(reg) a.as_ptr() => _,
+        p = in(reg) p.as_ptr(),
+        inv = in(reg) inv,
+        t0 = out(reg) t0,
+        t1 = out(reg) t1,
+        t2 = out(reg) t2,
+        t3 = out(reg) t3,
+        t4 = out(reg) _,
+        t5 = out(reg) _,
+        t6 = out(reg) _,
+        t7 = out(reg) _,
+        out("rax") _,
+        out("rdx") _,
+        options(pure, readonly, nostack),
+    );
+    reduce_once([t0, t1, t2, t3], p)
+}
+
+/// Subtract `p` once from a value in `[0, 2p)`, without branching.
+#[inline(always)]
+fn reduce_once(t: [u64; 4], p: [u64; 4]) -> [u64; 4] {
+    let (l0, brw) = sba(t[0], p[0]);
+    let (l1, brw) = sbb(t[1], p[1], brw);
+    let (l2, brw) = sbb(t[2], p[2], brw);
+    let (l3, brw) = sbb(t[3], p[3], brw);
+
+    let (l0, c) = adb(l0, p[0] & brw);
+    let (l1, c) = adc(l1, p[1] & brw, c);
+    let (l2, c) = adc(l2, p[2] & brw, c);
+    let l3 = l3.wrapping_add(p[3] & brw).wrapping_add(c);
+
+    [l0, l1, l2, l3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_adx, mul_adx, square_adx};
+    use crate::limbs::tests::{edge_cases, random_below};
+    use crate::limbs::{const_mul, const_square};
+    use crate::{fq, fr};
+
+    const ITERATIONS: usize = 100_000;
+
+    fn agree(p: [u64; 4], inv: u64) {
+        if !has_adx() {
+            return;
+        }
+        let mul = |a, b| unsafe { mul_adx(a, b, p, inv) };
+        let square = |a| unsafe { square_adx(a, p, inv) };
+        let edges = edge_cases(p);
+        for a in edges {
+            for b in edges {
+                assert_eq!(mul(a, b), const_mul(a, b, p, inv), "{a:x?} * {b:x?}");
+            }
+            assert_eq!(square(a), const_square(a, p, inv), "{a:x?}^2");
+        }
+        for _ in 0..ITERATIONS {
+            let (a, b) = (random_below(p), random_below(p));
+            assert_eq!(mul(a, b), const_mul(a, b, p, inv), "{a:x?} * {b:x?}");
+            assert_eq!(square(a), const_square(a, p, inv), "{a:x?}^2");
+        }
+    }
+
+    #[test]
+    fn fq_backends_agree() {
+        agree(fq::MODULUS, fq::INV);
+    }
+
+    #[test]
+    fn fr_backends_agree() {
+        agree(fr::MODULUS, fr::INV);
+    }
+}
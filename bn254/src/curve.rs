@@ -0,0 +1,156 @@
+//! Code shared by G1 over `Fq` and G2 over `Fq2`, which differ only in the
+//! coordinate field. The macros expand in the curve modules, so they use
+//! the private fields and the traits imported there.
+
+/// Batched affine addition, summation and normalization with Montgomery's
+/// trick, on an affine point type and its Jacobian counterpart over the
+/// coordinate field `$field`.
+macro_rules! affine_batch_impl {
+    ($affine:ident, $projective:ident, $field:ty) => {
+        impl $affine {
+            /// Add `rhs` to `lhs` pointwise in affine coordinates, with a
+            /// single field inversion for all the slopes (Montgomery's
+            /// trick). `rhs` and `scratch` must be at least as long as `lhs`.
+            pub fn batch_add_with_scratch(lhs: &mut [Self], rhs: &[Self], scratch: &mut [$field]) {
+                assert!(rhs.len() >= lhs.len() && scratch.len() >= lhs.len());
+                // scratch[i] holds the product of the non-zero denominators before i
+                let mut acc = <$field>::one();
+                for ((p, q), prefix) in lhs.iter().zip(rhs).zip(scratch.iter_mut()) {
+                    *prefix = acc;
+                    let (_, den) = p.slope(q);
+                    acc = <$field>::conditional_select(&(acc * den), &acc, den.is_zero());
+                }
+
+                // acc is a product of non-zero elements, so it is invertible
+                let mut acc = acc.invert().unwrap();
+                for ((p, q), prefix) in lhs.iter_mut().zip(rhs).zip(scratch.iter()).rev() {
+                    let (num, den) = p.slope(q);
+                    let inv = acc * *prefix;
+                    acc = <$field>::conditional_select(&(acc * den), &acc, den.is_zero());
+                    *p = p.add_with_slope(q, num * inv);
+                }
+            }
+
+            /// Sum `points` as a tree, halving their number each round with
+            /// one `batch_add_with_scratch`. `points` is overwritten and
+            /// `scratch` must be at least half as long.
+            pub fn sum_with_scratch(points: &mut [Self], scratch: &mut [$field]) -> Self {
+                let mut len = points.len();
+                while len > 1 {
+                    let half = len / 2;
+                    let (lhs, rhs) = points.split_at_mut(half);
+                    Self::batch_add_with_scratch(lhs, &rhs[..half], scratch);
+                    if len % 2 == 1 {
+                        // the odd point out joins the next round
+                        points[half] = points[len - 1];
+                        len = half + 1;
+                    } else {
+                        len = half;
+                    }
+                }
+                points.first().copied().unwrap_or(Self::identity())
+            }
+
+            /// `sum_with_scratch` on a copy of `points`, with the scratch
+            /// space on the heap.
+            #[cfg(feature = "alloc")]
+            pub fn sum(points: &[Self]) -> Self {
+                let mut points = points.to_vec();
+                let mut scratch = vec![<$field>::zero(); points.len() / 2];
+                Self::sum_with_scratch(&mut points, &mut scratch)
+            }
+
+            /// Numerator and denominator of the slope of the line through
+            /// `self` and `rhs`, the tangent when they are equal. The
+            /// denominator is zero for opposite points.
+            fn slope(&self, rhs: &Self) -> ($field, $field) {
+                let is_double = self.x.ct_eq(&rhs.x) & self.y.ct_eq(&rhs.y);
+                let xx = self.x.square();
+                let num =
+                    <$field>::conditional_select(&(rhs.y - self.y), &(xx.double() + xx), is_double);
+                let den =
+                    <$field>::conditional_select(&(rhs.x - self.x), &self.y.double(), is_double);
+                (num, den)
+            }
+
+            /// `self + rhs` given the slope from `slope`, with the identity
+            /// and opposite points resolved by selects.
+            fn add_with_slope(&self, rhs: &Self, slope: $field) -> Self {
+                let x = slope.square() - self.x - rhs.x;
+                let y = slope * (self.x - x) - self.y;
+                let sum = Self {
+                    x,
+                    y,
+                    is_infinity: false,
+                };
+                let is_opposite = self.x.ct_eq(&rhs.x) & !self.y.ct_eq(&rhs.y);
+                let sum = Self::conditional_select(&sum, &Self::identity(), is_opposite);
+                let sum = Self::conditional_select(&sum, rhs, self.ct_is_identity());
+                Self::conditional_select(&sum, self, rhs.ct_is_identity())
+            }
+        }
+
+        impl $projective {
+            /// Convert `points` to affine into `out` with a single field
+            /// inversion (Montgomery's trick). `out` must be as long as
+            /// `points`.
+            pub fn batch_normalize(points: &[Self], out: &mut [$affine]) {
+                assert_eq!(points.len(), out.len());
+                // out[i].x holds the product of the non-zero z before i
+                let mut acc = <$field>::one();
+                for (p, q) in points.iter().zip(out.iter_mut()) {
+                    q.x = acc;
+                    acc = <$field>::conditional_select(&(acc * p.z), &acc, p.z.is_zero());
+                }
+
+                // acc is a product of non-zero elements, so it is invertible
+                let mut acc = acc.invert().unwrap();
+                for (p, q) in points.iter().zip(out.iter_mut()).rev() {
+                    let is_identity = p.z.is_zero();
+                    let z_inv = acc * q.x;
+                    acc = <$field>::conditional_select(&(acc * p.z), &acc, is_identity);
+                    let z_inv2 = z_inv.square();
+                    let affine = $affine {
+                        x: p.x * z_inv2,
+                        y: p.y * z_inv2 * z_inv,
+                        is_infinity: false,
+                    };
+                    *q = $affine::conditional_select(&affine, &$affine::identity(), is_identity);
+                }
+            }
+        }
+    };
+}
+
+/// The odd-multiple tables of the GLV and GLS multiplications, on a
+/// Jacobian point type.
+macro_rules! projective_table_impl {
+    ($projective:ident) => {
+        impl $projective {
+            /// `[P, 3P, 5P, ..., 15P]`, the multiples a recoded digit
+            /// selects from.
+            fn odd_multiples(self) -> [Self; glv::TABLE_LEN] {
+                let double = self.double();
+                let mut table = [self; glv::TABLE_LEN];
+                for i in 1..table.len() {
+                    table[i] = table[i - 1] + double;
+                }
+                table
+            }
+
+            /// `digit * P` from the odd multiples of `P`, negated once more
+            /// when `neg` is set. Every entry is scanned.
+            fn lookup(table: &[Self; glv::TABLE_LEN], digit: i8, neg: Choice) -> Self {
+                let sign = digit >> 7;
+                let index = (((digit ^ sign) - sign) as u8) >> 1;
+                let mut p = table[0];
+                for (j, entry) in table.iter().enumerate() {
+                    p = Self::conditional_select(&p, entry, index.ct_eq(&(j as u8)));
+                }
+                Self::conditional_select(&p, &-p, Choice::from(sign as u8 & 1) ^ neg)
+            }
+        }
+    };
+}
+
+pub(crate) use {affine_batch_impl, projective_table_impl};
@@ -0,0 +1,143 @@
+//! Fixed-base scalar multiplication by precomputed windows.
+//!
+//! The scalar is recoded into 52 signed 5-bit digits `d_i` in `[-16, 16)`
+//! and the table holds `j * 32^i * P` for `j = 1..=16` in affine form, so
+//! `[k]P` is the sum of one looked-up entry per window: 52 mixed additions
+//! and no doublings. Wider windows save a few additions for twice the
+//! memory, 60 KiB on G1 and 113 KiB on G2 at this width. With `alloc` the
+//! table is built in place on the heap, without it the table is returned
+//! by value and the caller needs the stack space for it.
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec};
+use core::fmt;
+use core::ops::Mul;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::fr::Fr;
+use crate::g1::{G1Affine, G1Projective};
+use crate::g2::{G2Affine, G2Projective};
+
+/// Width of a window in bits, at most 6 for the digits to fit an `i8`.
+const WINDOW: usize = 5;
+
+/// Number of windows of a 256-bit scalar.
+const WINDOWS: usize = 256_usize.div_ceil(WINDOW);
+
+/// Multiples stored per window, the magnitudes of the non-zero digits.
+const TABLE_LEN: usize = 1 << (WINDOW - 1);
+
+#[cfg(feature = "alloc")]
+type Windows<P> = Box<[[P; TABLE_LEN]; WINDOWS]>;
+#[cfg(not(feature = "alloc"))]
+type Windows<P> = [[P; TABLE_LEN]; WINDOWS];
+
+/// Windows filled with `identity`, allocated directly on the heap when
+/// `alloc` is enabled so no copy of the table passes through the stack.
+fn identity_windows<P: Copy>(identity: P) -> Windows<P> {
+    #[cfg(feature = "alloc")]
+    let windows = vec![[identity; TABLE_LEN]; WINDOWS]
+        .into_boxed_slice()
+        .try_into()
+        .unwrap_or_else(|_| unreachable!());
+    #[cfg(not(feature = "alloc"))]
+    let windows = [[identity; TABLE_LEN]; WINDOWS];
+    windows
+}
+
+/// Precomputed multiples of a fixed base point such as the generator, for
+/// `[k]P` several times faster than variable-base multiplication. Built
+/// with `FixedBaseTable::from(G1Affine::generator())` and applied as
+/// `&table * k`.
+#[derive(Clone)]
+pub struct FixedBaseTable<P> {
+    windows: Windows<P>,
+}
+
+impl<P: fmt::Debug> fmt::Debug for FixedBaseTable<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBaseTable")
+            .field("base", &self.windows[0][0])
+            .field("windows", &WINDOWS)
+            .field("multiples", &TABLE_LEN)
+            .finish()
+    }
+}
+
+/// Signed digits of the canonical scalar, least significant first. Windows
+/// of `TABLE_LEN` and above borrow `2^WINDOW` from the next one; r < 2^254
+/// leaves the last window below `TABLE_LEN`.
+fn recode(k: Fr) -> [i8; WINDOWS] {
+    let mut bytes = k.to_bytes_le();
+    let bit = |i: usize| bytes.get(i / 8).map_or(0, |b| (b >> (i % 8)) & 1) as i8;
+    let mut digits = [0; WINDOWS];
+    let mut carry = 0;
+    for (i, d) in digits.iter_mut().enumerate() {
+        let v = (0..WINDOW).fold(0, |v, j| v | bit(WINDOW * i + j) << j) + carry;
+        carry = (v + TABLE_LEN as i8) >> WINDOW;
+        *d = v - (carry << WINDOW);
+    }
+    bytes.zeroize();
+    digits
+}
+
+/// Split a digit into its sign and magnitude without branching.
+fn sign_abs(digit: i8) -> (Choice, u8) {
+    let sign = digit >> 7;
+    (Choice::from(sign as u8 & 1), ((digit ^ sign) - sign) as u8)
+}
+
+/// The table of an affine point type, its lookup and `&table * k`.
+macro_rules! fixed_base_impl {
+    ($affine:ident, $projective:ident) => {
+        impl From<$affine> for FixedBaseTable<$affine> {
+            fn from(base: $affine) -> Self {
+                let mut windows = identity_windows($affine::identity());
+                let mut p = $projective::from(base);
+                for window in windows.iter_mut() {
+                    let mut multiples = [p; TABLE_LEN];
+                    for i in 1..TABLE_LEN {
+                        multiples[i] = multiples[i - 1] + p;
+                    }
+                    $projective::batch_normalize(&multiples, window);
+                    for _ in 0..WINDOW {
+                        p = p.double();
+                    }
+                }
+                Self { windows }
+            }
+        }
+
+        impl FixedBaseTable<$affine> {
+            /// `digit * 2^(WINDOW * i) * P`, scanning every entry of the
+            /// window.
+            fn lookup(&self, i: usize, digit: i8) -> $affine {
+                let (neg, abs) = sign_abs(digit);
+                let mut p = $affine::identity();
+                for (j, entry) in self.windows[i].iter().enumerate() {
+                    p = $affine::conditional_select(&p, entry, abs.ct_eq(&(j as u8 + 1)));
+                }
+                $affine::conditional_select(&p, &-p, neg)
+            }
+        }
+
+        impl Mul<Fr> for &FixedBaseTable<$affine> {
+            type Output = $projective;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn mul(self, mut rhs: Fr) -> $projective {
+                let mut digits = recode(rhs);
+                let mut res = $projective::identity();
+                for (i, &digit) in digits.iter().enumerate() {
+                    res += self.lookup(i, digit);
+                }
+                rhs.zeroize();
+                digits.zeroize();
+                res
+            }
+        }
+    };
+}
+
+fixed_base_impl!(G1Affine, G1Projective);
+fixed_base_impl!(G2Affine, G2Projective);
@@ -1,6 +1,17 @@
-use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub};
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-use crate::limbs::{add, double, invert, little_fermat, mont, mul, neg, square, sub};
+use crate::limbs::{
+    add, add_wide, conditional_select, const_mul, ct_eq, ct_is_zero, ct_lt, double, from_le_bytes,
+    from_str_radix, mont, mul, mul_wide, mul_wide_x2, neg, pow, random_limbs, reduce_wide_x2,
+    square, sub, sub_wide, to_le_bytes,
+};
+use crate::safegcd;
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
 
 pub(crate) const MODULUS: [u64; 4] = [
     0x3c208c16d87cfd47,
@@ -17,49 +28,266 @@ pub(crate) const R: [u64; 4] = [
     0x0e0a77c19a07df2f,
 ];
 
+/// R^2 = 2^512 mod q
+pub(crate) const R2: [u64; 4] = [
+    0xf32cfc5b538afa89,
+    0xb5e71911d44501fb,
+    0x47ab1eff0a417ff6,
+    0x06d89f71cab8351f,
+];
+
+/// R^3 = 2^768 mod q
+pub(crate) const R3: [u64; 4] = [
+    0xb1cd6dafda1530df,
+    0x62f210e6a7283db6,
+    0xef7f0b0c0ada0afb,
+    0x20fd6e902d592544,
+];
+
 /// INV = -(q^{-1} mod 2^64) mod 2^64
 pub(crate) const INV: u64 = 0x87d20782e4866389;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) struct Fq(pub(crate) [u64; 4]);
+/// INV62 = q^{-1} mod 2^62
+pub(crate) const INV62: u64 = 0x382df87d1b799c77;
+
+/// (q + 1) / 4, square root exponent since q = 3 mod 4
+const SQRT_EXP: [u64; 4] = [
+    0x4f082305b61f3f52,
+    0x65e05aa45a1c72a3,
+    0x6e14116da0605617,
+    0x0c19139cb84c680a,
+];
+
+/// (q - 1) / 2, Euler's criterion exponent
+const LEGENDRE_EXP: [u64; 4] = [
+    0x9e10460b6c3e7ea3,
+    0xcbc0b548b438e546,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+];
+
+/// Base field element, held in Montgomery form. Conversions to and from
+/// integers and bytes always go through the canonical value.
+#[derive(Clone, Copy, Default)]
+pub struct Fq(pub(crate) [u64; 4]);
 
 impl Fq {
-    pub(crate) const fn zero() -> Self {
+    pub const fn zero() -> Self {
         Self([0; 4])
     }
 
-    pub(crate) const fn is_zero(self) -> bool {
-        self.0[0] == 0 && self.0[1] == 0 && self.0[2] == 0 && self.0[3] == 0
+    pub fn is_zero(self) -> Choice {
+        ct_is_zero(self.0)
     }
 
-    pub(crate) const fn one() -> Self {
+    pub const fn one() -> Self {
         Self(R)
     }
 
-    pub(crate) const fn double(self) -> Self {
+    pub fn random<R: RngCore>(rand: &mut R) -> Self {
+        Self(random_limbs(rand, R2, R3, MODULUS, INV))
+    }
+
+    pub const fn from_u64(val: u64) -> Self {
+        Self::to_mont_form([val, 0, 0, 0])
+    }
+
+    /// Parse the canonical integer in the given radix, rejecting values not
+    /// below the modulus.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        from_str_radix(s, radix, MODULUS).map(Self::to_mont_form)
+    }
+
+    /// Decode the canonical little-endian integer, rejecting values not
+    /// below the modulus.
+    pub fn from_bytes_le(bytes: &[u8; 32]) -> CtOption<Self> {
+        let raw = from_le_bytes(bytes);
+        CtOption::new(Self::to_mont_form(raw), ct_lt(raw, MODULUS))
+    }
+
+    pub fn from_bytes_be(bytes: &[u8; 32]) -> CtOption<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+
+    pub fn to_bytes_le(self) -> [u8; 32] {
+        to_le_bytes(self.to_raw())
+    }
+
+    /// Big-endian encoding, the layout of an EVM uint256 word
+    pub fn to_bytes_be(self) -> [u8; 32] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    pub const fn double(self) -> Self {
         Self(double(self.0, MODULUS))
     }
 
-    pub(crate) const fn square(self) -> Self {
+    pub fn square(self) -> Self {
         Self(square(self.0, MODULUS, INV))
     }
 
-    pub(crate) fn invert(self) -> Option<Self> {
-        match invert(self.0, little_fermat(MODULUS), R, MODULUS, INV) {
-            Some(x) => Some(Self(x)),
-            None => None,
+    /// Raise to the power of the little-endian integer `exp`, in time
+    /// independent of both operands.
+    pub fn pow(self, exp: [u64; 4]) -> Self {
+        Self(pow(self.0, exp, R, MODULUS, INV))
+    }
+
+    /// Square root by a single exponentiation, since q = 3 mod 4.
+    pub fn sqrt(self) -> CtOption<Self> {
+        let root = self.pow(SQRT_EXP);
+        CtOption::new(root, root.square().ct_eq(&self))
+    }
+
+    /// Legendre symbol: 1 for non-zero squares, -1 for non-squares and 0
+    /// for zero.
+    pub fn legendre(self) -> i8 {
+        let symbol = self.pow(LEGENDRE_EXP);
+        let res = i8::conditional_select(&-1, &1, symbol.ct_eq(&Self::one()));
+        i8::conditional_select(&res, &0, self.is_zero())
+    }
+
+    pub fn is_square(self) -> Choice {
+        !self.pow(LEGENDRE_EXP).ct_eq(&-Self::one())
+    }
+
+    /// Whether the canonical integer exceeds (q - 1) / 2, which picks one
+    /// of `y` and `-y` for point compression.
+    pub(crate) fn is_lexicographically_largest(self) -> Choice {
+        ct_lt(LEGENDRE_EXP, self.to_raw())
+    }
+
+    pub fn invert(self) -> CtOption<Self> {
+        // (aR)^{-1} = a^{-1}R^{-1}, and a Montgomery multiplication by R^3
+        // brings it back to a^{-1}R
+        let inv = mul(safegcd::invert(self.0, MODULUS, INV62), R3, MODULUS, INV);
+        CtOption::new(Self(inv), !self.is_zero())
+    }
+
+    /// Invert every element of `elems` in place with a single field inversion
+    /// (Montgomery's trick). Zero elements are left as zero. `scratch` must
+    /// be at least as long as `elems`.
+    pub fn batch_invert_with_scratch(elems: &mut [Self], scratch: &mut [Self]) {
+        assert!(scratch.len() >= elems.len());
+        // scratch[i] holds the product of the non-zero elems[..i]
+        let mut acc = Self::one();
+        for (elem, prefix) in elems.iter().zip(scratch.iter_mut()) {
+            *prefix = acc;
+            acc = Self::conditional_select(&(acc * *elem), &acc, elem.is_zero());
         }
+
+        // acc is a product of non-zero elements, so it is invertible
+        let mut acc = acc.invert().unwrap();
+        for (elem, prefix) in elems.iter_mut().zip(scratch.iter()).rev() {
+            let is_zero = elem.is_zero();
+            let inv = acc * *prefix;
+            acc = Self::conditional_select(&(acc * *elem), &acc, is_zero);
+            *elem = Self::conditional_select(&inv, elem, is_zero);
+        }
+    }
+
+    /// `batch_invert_with_scratch` with the scratch space on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn batch_invert(elems: &mut [Self]) {
+        let mut scratch = vec![Self::zero(); elems.len()];
+        Self::batch_invert_with_scratch(elems, &mut scratch);
     }
 
     pub(crate) const fn to_mont_form(val: [u64; 4]) -> Self {
-        Self(mont(
-            [val[0], val[1], val[2], val[3], 0, 0, 0, 0],
+        Self(const_mul(val, R2, MODULUS, INV))
+    }
+
+    /// Convert from Montgomery form to the canonical integer limbs.
+    pub(crate) const fn to_raw(self) -> [u64; 4] {
+        mont(
+            [self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0],
             MODULUS,
             INV,
-        ))
+        )
+    }
+
+    /// `self * rhs` left unreduced, for lazy reduction in the tower.
+    #[inline(always)]
+    pub(crate) fn mul_wide(self, rhs: Self) -> FqWide {
+        FqWide(mul_wide(self.0, rhs.0))
+    }
+
+    /// Two independent `mul_wide`, which the Wasm backend computes at once.
+    #[inline(always)]
+    pub(crate) fn mul_wide_x2(a: [Self; 2], b: [Self; 2]) -> [FqWide; 2] {
+        mul_wide_x2([a[0].0, a[1].0], [b[0].0, b[1].0]).map(FqWide)
+    }
+}
+
+/// A double-width value modulo `q * R`, such as an unreduced product of two
+/// elements in Montgomery form. Sums and differences of products stay in
+/// this form and pay for a single Montgomery reduction at the end, as in
+/// Aranha et al., Faster explicit formulas for computing pairings over
+/// ordinary curves, https://eprint.iacr.org/2010/526.pdf
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct FqWide(pub(crate) [u64; 8]);
+
+impl FqWide {
+    #[inline(always)]
+    pub(crate) const fn double(self) -> Self {
+        Self(add_wide(self.0, self.0, MODULUS))
+    }
+
+    /// The Montgomery reductions of two values back to `Fq`, which are
+    /// correct for anything below `q * R`.
+    #[inline(always)]
+    pub(crate) fn reduce_x2(t: [Self; 2]) -> [Fq; 2] {
+        reduce_wide_x2([t[0].0, t[1].0], MODULUS, INV).map(Fq)
     }
 }
 
+impl fmt::LowerHex for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.to_bytes_be() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+impl fmt::Debug for Fq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+impl ConstantTimeEq for Fq {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ct_eq(self.0, other.0)
+    }
+}
+
+impl ConditionallySelectable for Fq {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(conditional_select(a.0, b.0, choice))
+    }
+}
+
+impl PartialEq for Fq {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fq {}
+
 impl Add for Fq {
     type Output = Self;
 
@@ -82,6 +310,12 @@ impl Sub for Fq {
     }
 }
 
+impl SubAssign for Fq {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
 impl Neg for Fq {
     type Output = Self;
 
@@ -103,3 +337,125 @@ impl MulAssign for Fq {
         *self = *self * rhs;
     }
 }
+
+impl Add for FqWide {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        Self(add_wide(self.0, rhs.0, MODULUS))
+    }
+}
+
+impl Sub for FqWide {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        Self(sub_wide(self.0, rhs.0, MODULUS))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::limbs::tests::{edge_cases, random_below};
+
+    /// `q * 2^256 - 1`, the largest value an `FqWide` holds.
+    pub(crate) const TOP: FqWide = FqWide([
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        MODULUS[0] - 1,
+        MODULUS[1],
+        MODULUS[2],
+        MODULUS[3],
+    ]);
+
+    const ONE: FqWide = FqWide([1, 0, 0, 0, 0, 0, 0, 0]);
+
+    pub(crate) fn reduce(t: FqWide) -> Fq {
+        FqWide::reduce_x2([t, FqWide::default()])[0]
+    }
+
+    /// Elements at the edges of `[0, q)` followed by a random one.
+    pub(crate) fn edge_elements() -> [Fq; 8] {
+        let e = edge_cases(MODULUS).map(Fq);
+        [
+            e[0],
+            e[1],
+            e[2],
+            e[3],
+            e[4],
+            e[5],
+            e[6],
+            Fq(random_below(MODULUS)),
+        ]
+    }
+
+    /// Wide values at the edges of `[0, q * 2^256)`.
+    fn wide_edges() -> [FqWide; 6] {
+        let mut low = random_below([u64::MAX; 4]).to_vec();
+        low.extend_from_slice(&random_below(MODULUS));
+        [
+            FqWide::default(),
+            ONE,
+            TOP,
+            TOP - ONE,
+            FqWide([
+                0,
+                0,
+                0,
+                0,
+                MODULUS[0] - 1,
+                MODULUS[1],
+                MODULUS[2],
+                MODULUS[3],
+            ]),
+            FqWide(low.try_into().unwrap()),
+        ]
+    }
+
+    #[test]
+    fn fq_wide_bounds_test() {
+        let zero = FqWide::default();
+        assert_eq!((TOP + ONE).0, [0; 8]);
+        assert_eq!((zero - ONE).0, TOP.0);
+        assert_eq!((zero - TOP).0, ONE.0);
+        assert_eq!((TOP + TOP).0, (TOP - ONE).0);
+        assert_eq!(TOP.double().0, (TOP - ONE).0);
+        assert_eq!(reduce(TOP) + reduce(ONE), Fq::zero());
+        assert_eq!(reduce(zero), Fq::zero());
+
+        for a in wide_edges() {
+            for b in wide_edges() {
+                // exact inverses of each other modulo q * 2^256
+                assert_eq!(((a - b) + b).0, a.0);
+                assert_eq!(((a + b) - b).0, a.0);
+                assert_eq!(reduce(a - b), reduce(a) - reduce(b));
+                assert_eq!(reduce(a + b), reduce(a) + reduce(b));
+            }
+            assert_eq!(reduce(a.double()), reduce(a).double());
+        }
+    }
+
+    #[test]
+    fn fq_wide_products_test() {
+        for a in edge_elements() {
+            for b in edge_elements() {
+                let ab = a.mul_wide(b);
+                assert_eq!(reduce(ab), a * b);
+                assert_eq!(Fq::mul_wide_x2([a, b], [b, a]).map(|t| t.0), [ab.0; 2]);
+
+                // sums and differences of products, which underflow below zero
+                for c in edge_elements() {
+                    let cc = c.mul_wide(c);
+                    assert_eq!(reduce(ab - cc), a * b - c.square());
+                    assert_eq!(reduce(cc - ab + ab), c.square());
+                    assert_eq!(reduce(ab + cc), a * b + c.square());
+                }
+            }
+        }
+    }
+}
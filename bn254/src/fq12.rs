@@ -1,32 +1,93 @@
-use core::ops::{Mul, MulAssign};
+use core::fmt;
+use core::ops::{Add, Mul, MulAssign, Neg, Sub};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 use crate::fq::Fq;
 use crate::fq2::Fq2;
-use crate::fq6::Fq6;
+use crate::fq6::{Fq6, Fq6Wide};
 use crate::g1::G1Affine;
 use crate::g2::PairingCoeff;
 use crate::gt::Gt;
-use crate::params::{BN_X, FROBENIUS_COEFF_FQ12_C1};
+use crate::limbs::to_bits;
+use crate::params::{BN_X_NAF, FROBENIUS_COEFF_FQ12_C1};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Fq12([Fq6; 2]);
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fq12(pub(crate) [Fq6; 2]);
 
 impl Fq12 {
+    pub const fn zero() -> Self {
+        Self([Fq6::zero(), Fq6::zero()])
+    }
+
     pub const fn one() -> Self {
         Self([Fq6::one(), Fq6::zero()])
     }
 
+    pub fn random<R: RngCore>(rand: &mut R) -> Self {
+        Self([Fq6::random(rand), Fq6::random(rand)])
+    }
+
+    pub const fn from_u64(val: u64) -> Self {
+        Self([Fq6::from_u64(val), Fq6::zero()])
+    }
+
+    /// Parse a base field element as `Fq::from_str_radix` does and embed it
+    /// as `c0.c0.c0`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        Fq6::from_str_radix(s, radix).map(|c0| Self([c0, Fq6::zero()]))
+    }
+
+    /// Decode the twelve Fq coefficients in tower order, `c0.c0.c0` first,
+    /// each canonical and little-endian.
+    pub fn from_bytes_le(bytes: &[u8; 384]) -> CtOption<Self> {
+        let c0 = Fq6::from_bytes_le(bytes[..192].try_into().unwrap());
+        let c1 = Fq6::from_bytes_le(bytes[192..].try_into().unwrap());
+        c0.and_then(|c0| c1.map(|c1| Self([c0, c1])))
+    }
+
+    /// Byte reversal of the little-endian encoding.
+    pub fn from_bytes_be(bytes: &[u8; 384]) -> CtOption<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+
+    pub fn to_bytes_le(self) -> [u8; 384] {
+        let mut bytes = [0; 384];
+        bytes[..192].copy_from_slice(&self.0[0].to_bytes_le());
+        bytes[192..].copy_from_slice(&self.0[1].to_bytes_le());
+        bytes
+    }
+
+    pub fn to_bytes_be(self) -> [u8; 384] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Complex squaring, `(a + b)(a + v * b) - ab - v * ab + 2ab * w`.
     pub fn square(self) -> Self {
-        let re = self.0[0].square() - self.0[1].square();
-        let im = (self.0[0] * self.0[1]).double();
-        Self([re, im])
+        let [a, b] = self.0;
+        let ab = a * b;
+        let t = (a + b) * (a + b.mul_by_nonres());
+        Self([t - ab - ab.mul_by_nonres(), ab.double()])
     }
 
     pub fn square_assign(&mut self) {
         *self = self.square()
     }
 
-    pub fn invert(self) -> Option<Self> {
+    pub fn pow(self, exp: [u64; 4]) -> Self {
+        let mut res = Self::one();
+        for &bit in to_bits(exp).iter() {
+            res = res.square();
+            res = Self::conditional_select(&res, &(res * self), Choice::from(bit));
+        }
+        res
+    }
+
+    pub fn invert(self) -> CtOption<Self> {
         (self.0[0].square() - self.0[1].square().mul_by_nonres())
             .invert()
             .map(|t| Self([self.0[0] * t, self.0[1] * -t]))
@@ -47,21 +108,11 @@ impl Fq12 {
     }
 
     pub(crate) fn mul_by_034(self, c0: Fq2, c3: Fq2, c4: Fq2) -> Self {
-        let t0 = Fq6([
-            self.0[0].0[0] * c0,
-            self.0[0].0[1] * c0,
-            self.0[0].0[2] * c0,
-        ]);
-        let mut t1 = self.0[1];
-        t1 = t1.mul_by_01(c3, c4);
-        let o = c0 + c3;
-        let mut t2 = self.0[0] + self.0[1];
-        t2 = t2.mul_by_01(o, c4);
-        t2 -= t0;
-        let b = t2 - t1;
-        t1 = t1.mul_by_nonres();
-        let a = t0 + t1;
-        Self([a, b])
+        let [a, b] = self.0;
+        let t0 = Fq6Wide(a.0.map(|c| c.mul_wide(c0)));
+        let t1 = b.mul_by_01_wide(c3, c4);
+        let t2 = (a + b).mul_by_01_wide(c0 + c3, c4);
+        Self([(t0 + t1.mul_by_nonres()).reduce(), (t2 - t0 - t1).reduce()])
     }
 
     pub fn conjugate(self) -> Self {
@@ -76,7 +127,7 @@ impl Fq12 {
         Self([c0, c1])
     }
 
-    fn frobenius_maps(self, power: usize) -> Self {
+    pub(crate) fn frobenius_maps(self, power: usize) -> Self {
         let c0 = self.0[0].frobenius_maps(power);
         let c1 = self.0[1].frobenius_maps(power);
         let c1 = Fq6([
@@ -88,73 +139,67 @@ impl Fq12 {
         Self([c0, c1])
     }
 
-    pub fn final_exp(self) -> Gt {
-        fn fp4_square(a: Fq2, b: Fq2) -> (Fq2, Fq2) {
-            let t0 = a.square();
-            let t1 = b.square();
-            let mut t2 = t1.mul_by_nonres();
-            let c0 = t2 + t0;
-            t2 = a + b;
-            t2 = t2.square();
-            t2 -= t0;
-            let c1 = t2 - t1;
-
-            (c0, c1)
-        }
-        // Adaptation of Algorithm 5.5.4, Guide to Pairing-Based Cryptography
-        // Faster Squaring in the Cyclotomic Subgroup of Sixth Degree Extensions
-        // https://eprint.iacr.org/2009/565.pdf
-        #[must_use]
-        fn cyclotomic_square(f: Fq12) -> Fq12 {
-            let mut z0 = f.0[0].0[0];
-            let mut z4 = f.0[0].0[1];
-            let mut z3 = f.0[0].0[2];
-            let mut z2 = f.0[1].0[0];
-            let mut z1 = f.0[1].0[1];
-            let mut z5 = f.0[1].0[2];
-
-            let (t0, t1) = fp4_square(z0, z1);
-
-            // For A
-            z0 = t0 - z0;
-            z0 = z0.double() + t0;
-
-            z1 = t1 + z1;
-            z1 = z1.double() + t1;
-
-            let (mut t0, t1) = fp4_square(z2, z3);
-            let (t2, t3) = fp4_square(z4, z5);
-
-            // For C
-            z4 = t0 - z4;
-            z4 = z4.double() + t0;
-
-            z5 = t1 + z5;
-            z5 = z5.double() + t1;
-
-            // For B
-            t0 = t3.mul_by_nonres();
-            z2 = t0 + z2;
-            z2 = z2.double() + t0;
-
-            z3 = t2 - z3;
-            z3 = z3.double() + t2;
-
-            Fq12([Fq6([z0, z4, z3]), Fq6([z2, z1, z5])])
-        }
+    // Adaptation of Algorithm 5.5.4, Guide to Pairing-Based Cryptography
+    // Faster Squaring in the Cyclotomic Subgroup of Sixth Degree Extensions
+    // https://eprint.iacr.org/2009/565.pdf
+    #[must_use]
+    pub(crate) fn cyclotomic_square(self) -> Self {
+        let mut z0 = self.0[0].0[0];
+        let mut z4 = self.0[0].0[1];
+        let mut z3 = self.0[0].0[2];
+        let mut z2 = self.0[1].0[0];
+        let mut z1 = self.0[1].0[1];
+        let mut z5 = self.0[1].0[2];
+
+        let (t0, t1) = fp4_square(z0, z1);
+
+        // For A
+        z0 = t0 - z0;
+        z0 = z0.double() + t0;
+
+        z1 = t1 + z1;
+        z1 = z1.double() + t1;
+
+        let (mut t0, t1) = fp4_square(z2, z3);
+        let (t2, t3) = fp4_square(z4, z5);
 
-        #[must_use]
-        fn cycolotomic_exp(f: Fq12) -> Fq12 {
-            let mut res = Fq12::one();
-            for is_one in (0..64).rev().map(|i| ((BN_X >> i) & 1) == 1) {
-                res = cyclotomic_square(res);
-                if is_one {
-                    res *= f;
-                }
+        // For C
+        z4 = t0 - z4;
+        z4 = z4.double() + t0;
+
+        z5 = t1 + z5;
+        z5 = z5.double() + t1;
+
+        // For B
+        t0 = t3.mul_by_nonres();
+        z2 = t0 + z2;
+        z2 = z2.double() + t0;
+
+        z3 = t2 - z3;
+        z3 = z3.double() + t2;
+
+        Fq12([Fq6([z0, z4, z3]), Fq6([z2, z1, z5])])
+    }
+
+    /// Exponentiation by x in the cyclotomic subgroup, by Granger-Scott
+    /// squarings over the NAF of x. The inverse there is the conjugate, so
+    /// the -1 digits cost a multiplication like the 1 digits.
+    #[must_use]
+    pub(crate) fn cycolotomic_exp(self) -> Self {
+        let inv = self.conjugate();
+        let mut res = Fq12::one();
+        for &digit in BN_X_NAF.iter().rev() {
+            res = res.cyclotomic_square();
+            if digit > 0 {
+                res *= self;
+            } else if digit < 0 {
+                res *= inv;
             }
-            res
         }
+        res
+    }
 
+    pub fn final_exp(self) -> Gt {
         let f = self;
         let f1 = f.conjugate();
         Gt(f.invert()
@@ -166,9 +211,9 @@ impl Fq12 {
                 let fp2 = r.frobenius_maps(2);
                 let fp3 = fp2.frobenius_maps(1);
 
-                let fu = cycolotomic_exp(r);
-                let fu2 = cycolotomic_exp(fu);
-                let fu3 = cycolotomic_exp(fu2);
+                let fu = r.cycolotomic_exp();
+                let fu2 = fu.cycolotomic_exp();
+                let fu3 = fu2.cycolotomic_exp();
 
                 let y3 = fu.frobenius_maps(1).conjugate();
 
@@ -183,15 +228,15 @@ impl Fq12 {
 
                 let y4 = (fu * fu2p).conjugate();
 
-                let mut y6 = cyclotomic_square((fu3 * fu3p).conjugate()) * y4 * y5;
+                let mut y6 = (fu3 * fu3p).conjugate().cyclotomic_square() * y4 * y5;
 
                 let mut t1 = y3 * y5 * y6;
                 y6 *= y2;
-                t1 = cyclotomic_square(cyclotomic_square(t1) * y6);
+                t1 = (t1.cyclotomic_square() * y6).cyclotomic_square();
 
                 let mut t0 = t1 * y1;
                 t1 *= y0;
-                t0 = cyclotomic_square(t0) * t1;
+                t0 = t0.cyclotomic_square() * t1;
                 t0
             })
             .unwrap())
@@ -291,6 +336,71 @@ impl Fq12 {
     }
 }
 
+impl fmt::LowerHex for Fq12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.to_bytes_be() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Fq12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}) + ({}) * w", self.0[0], self.0[1])
+    }
+}
+
+impl ConstantTimeEq for Fq12 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[0].ct_eq(&other.0[0]) & self.0[1].ct_eq(&other.0[1])
+    }
+}
+
+impl ConditionallySelectable for Fq12 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self([
+            Fq6::conditional_select(&a.0[0], &b.0[0], choice),
+            Fq6::conditional_select(&a.0[1], &b.0[1], choice),
+        ])
+    }
+}
+
+impl PartialEq for Fq12 {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fq12 {}
+
+impl Add for Fq12 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self([self.0[0] + rhs.0[0], self.0[1] + rhs.0[1]])
+    }
+}
+
+impl Neg for Fq12 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1]])
+    }
+}
+
+impl Sub for Fq12 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self([self.0[0] - rhs.0[0], self.0[1] - rhs.0[1]])
+    }
+}
+
 impl Mul<Fq12> for Fq12 {
     type Output = Self;
 
@@ -314,3 +424,12 @@ impl MulAssign for Fq12 {
         *self = *self * rhs;
     }
 }
+
+fn fp4_square(a: Fq2, b: Fq2) -> (Fq2, Fq2) {
+    let t0 = a.square_wide();
+    let t1 = b.square_wide();
+    let c0 = t1.mul_by_nonres() + t0;
+    let c1 = (a + b).square_wide() - t0 - t1;
+
+    (c0.reduce(), c1.reduce())
+}
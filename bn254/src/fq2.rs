@@ -1,43 +1,167 @@
-use crate::fq::Fq;
+use crate::fq::{Fq, FqWide};
+use crate::limbs::to_bits;
 use crate::params::FROBENIUS_COEFF_FQ2_C1;
 
+use core::fmt;
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) struct Fq2(pub(crate) [Fq; 2]);
+/// (q - 3) / 4
+const SQRT_EXP: [u64; 4] = [
+    0x4f082305b61f3f51,
+    0x65e05aa45a1c72a3,
+    0x6e14116da0605617,
+    0x0c19139cb84c680a,
+];
+
+/// (q - 1) / 2
+const LEGENDRE_EXP: [u64; 4] = [
+    0x9e10460b6c3e7ea3,
+    0xcbc0b548b438e546,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+];
+
+/// Quadratic extension `Fq[u] / (u^2 + 1)`, stored as `c0 + c1 * u`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fq2(pub(crate) [Fq; 2]);
 
 impl Fq2 {
-    pub(crate) const fn zero() -> Self {
+    pub const fn new(c0: Fq, c1: Fq) -> Self {
+        Self([c0, c1])
+    }
+
+    pub const fn zero() -> Self {
         Self([Fq::zero(); 2])
     }
 
-    fn is_zero(self) -> bool {
-        self.0[0].is_zero() && self.0[1].is_zero()
+    pub fn is_zero(self) -> Choice {
+        self.0[0].is_zero() & self.0[1].is_zero()
     }
 
-    pub(crate) const fn one() -> Self {
+    pub const fn one() -> Self {
         Self([Fq::one(), Fq::zero()])
     }
 
-    pub(crate) fn double(self) -> Self {
+    pub fn random<R: RngCore>(rand: &mut R) -> Self {
+        Self([Fq::random(rand), Fq::random(rand)])
+    }
+
+    pub const fn from_u64(val: u64) -> Self {
+        Self([Fq::from_u64(val), Fq::zero()])
+    }
+
+    /// Parse a base field element as `Fq::from_str_radix` does and embed it
+    /// as `c0`, like `from_u64`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        Fq::from_str_radix(s, radix).map(|c0| Self([c0, Fq::zero()]))
+    }
+
+    /// Decode `c0 || c1`, each canonical and little-endian.
+    pub fn from_bytes_le(bytes: &[u8; 64]) -> CtOption<Self> {
+        let c0 = Fq::from_bytes_le(bytes[..32].try_into().unwrap());
+        let c1 = Fq::from_bytes_le(bytes[32..].try_into().unwrap());
+        c0.and_then(|c0| c1.map(|c1| Self([c0, c1])))
+    }
+
+    /// Decode `c1 || c0`, each canonical and big-endian, the order the EVM
+    /// precompiles use for G2 coordinates.
+    pub fn from_bytes_be(bytes: &[u8; 64]) -> CtOption<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+
+    pub fn to_bytes_le(self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&self.0[0].to_bytes_le());
+        bytes[32..].copy_from_slice(&self.0[1].to_bytes_le());
+        bytes
+    }
+
+    pub fn to_bytes_be(self) -> [u8; 64] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    pub fn double(self) -> Self {
         Self([self.0[0].double(), self.0[1].double()])
     }
 
-    pub(crate) fn square(self) -> Self {
-        let re = self.0[0].square() - self.0[1].square();
-        let im = (self.0[0] * self.0[1]).double();
-        Self([re, im])
+    pub fn square(self) -> Self {
+        self.square_wide().reduce()
     }
 
-    pub(crate) fn invert(self) -> Option<Self> {
-        match self.is_zero() {
-            true => None,
-            _ => {
-                let t = self.0[0].square() + self.0[1].square();
-                let t_inv = t.invert().unwrap();
-                Some(Self([t_inv * self.0[0], t_inv * -self.0[1]]))
-            }
+    /// Karatsuba product with three base multiplications, left unreduced.
+    #[inline(always)]
+    pub(crate) fn mul_wide(self, rhs: Self) -> Fq2Wide {
+        let [a0, a1] = self.0;
+        let [b0, b1] = rhs.0;
+        let [t0, t1] = Fq::mul_wide_x2([a0, a1], [b0, b1]);
+        let t2 = (a0 + a1).mul_wide(b0 + b1);
+        Fq2Wide([t0 - t1, t2 - t0 - t1])
+    }
+
+    /// `(c0 + c1)(c0 - c1) + 2 c0 c1 u`, left unreduced.
+    #[inline(always)]
+    pub(crate) fn square_wide(self) -> Fq2Wide {
+        let [a0, a1] = self.0;
+        Fq2Wide(Fq::mul_wide_x2([a0 + a1, a0.double()], [a0 - a1, a1]))
+    }
+
+    pub fn invert(self) -> CtOption<Self> {
+        // the norm is zero exactly when self is, since -1 is a non-residue
+        self.norm()
+            .invert()
+            .map(|t_inv| Self([t_inv * self.0[0], t_inv * -self.0[1]]))
+    }
+
+    /// Raise to the power of the little-endian integer `exp`, in time
+    /// independent of both operands.
+    pub fn pow(self, exp: [u64; 4]) -> Self {
+        let mut res = Self::one();
+        for &bit in to_bits(exp).iter() {
+            res = res.square();
+            res = Self::conditional_select(&res, &(res * self), Choice::from(bit));
         }
+        res
+    }
+
+    /// Square root by Algorithm 9 of https://eprint.iacr.org/2012/685.pdf,
+    /// which applies since q = 3 mod 4.
+    pub fn sqrt(self) -> CtOption<Self> {
+        let a1 = self.pow(SQRT_EXP);
+        let alpha = a1.square() * self;
+        let x0 = a1 * self;
+
+        // alpha = -1 makes the root u * x0, otherwise (1 + alpha)^((q-1)/2) * x0
+        let u_x0 = Self([-x0.0[1], x0.0[0]]);
+        let b = (Self::one() + alpha).pow(LEGENDRE_EXP);
+        let root = Self::conditional_select(&(b * x0), &u_x0, alpha.ct_eq(&-Self::one()));
+
+        CtOption::new(root, root.square().ct_eq(&self))
+    }
+
+    /// Legendre symbol, which equals the one of the norm in Fq.
+    pub fn legendre(self) -> i8 {
+        self.norm().legendre()
+    }
+
+    pub fn is_square(self) -> Choice {
+        self.norm().is_square()
+    }
+
+    /// Lexicographic order on `(c1, c0)`, the order of `to_bytes_be`.
+    pub(crate) fn is_lexicographically_largest(self) -> Choice {
+        let [c0, c1] = self.0;
+        c1.is_lexicographically_largest() | (c1.is_zero() & c0.is_lexicographically_largest())
+    }
+
+    /// `c0^2 + c1^2`, the product of self and its conjugate
+    fn norm(self) -> Fq {
+        self.0[0].square() + self.0[1].square()
     }
 
     /// Multiply this element by quadratic nonresidue 9 + u.
@@ -71,6 +195,72 @@ impl Fq2 {
     }
 }
 
+/// An unreduced `Fq2` product, see `FqWide`.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Fq2Wide(pub(crate) [FqWide; 2]);
+
+impl Fq2Wide {
+    #[inline(always)]
+    pub(crate) fn double(self) -> Self {
+        Self([self.0[0].double(), self.0[1].double()])
+    }
+
+    /// Multiply by the non-residue 9 + u, as `Fq2::mul_by_nonres`.
+    #[inline(always)]
+    pub(crate) fn mul_by_nonres(self) -> Self {
+        let [x, y] = self.0;
+        let x8 = x.double().double().double();
+        let y8 = y.double().double().double();
+        Self([x8 + x - y, y8 + y + x])
+    }
+
+    #[inline(always)]
+    pub(crate) fn reduce(self) -> Fq2 {
+        Fq2(FqWide::reduce_x2(self.0))
+    }
+}
+
+impl fmt::LowerHex for Fq2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.to_bytes_be() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Fq2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {} * u", self.0[0], self.0[1])
+    }
+}
+
+impl ConstantTimeEq for Fq2 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[0].ct_eq(&other.0[0]) & self.0[1].ct_eq(&other.0[1])
+    }
+}
+
+impl ConditionallySelectable for Fq2 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self([
+            Fq::conditional_select(&a.0[0], &b.0[0], choice),
+            Fq::conditional_select(&a.0[1], &b.0[1], choice),
+        ])
+    }
+}
+
+impl PartialEq for Fq2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fq2 {}
+
 impl Add for Fq2 {
     type Output = Self;
 
@@ -114,9 +304,7 @@ impl Mul<Fq2> for Fq2 {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self {
-        let re = (self.0[0] * rhs.0[0]) - (self.0[1] * rhs.0[1]);
-        let im = (self.0[0] * rhs.0[1]) + (self.0[1] * rhs.0[0]);
-        Self([re, im])
+        self.mul_wide(rhs).reduce()
     }
 }
 
@@ -125,3 +313,64 @@ impl MulAssign for Fq2 {
         *self = *self * rhs;
     }
 }
+
+impl Add for Fq2Wide {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        Self([self.0[0] + rhs.0[0], self.0[1] + rhs.0[1]])
+    }
+}
+
+impl Sub for Fq2Wide {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        Self([self.0[0] - rhs.0[0], self.0[1] - rhs.0[1]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fq::tests::{edge_elements, reduce, TOP};
+
+    /// Elements with coefficients at the edges of `[0, q)`.
+    fn edge_elements2() -> impl Iterator<Item = Fq2> {
+        let e = edge_elements();
+        e.into_iter()
+            .flat_map(move |c0| e.into_iter().map(move |c1| Fq2([c0, c1])))
+    }
+
+    #[test]
+    fn fq2_wide_products_test() {
+        for a in edge_elements2() {
+            for b in edge_elements2() {
+                let [a0, a1] = a.0;
+                let [b0, b1] = b.0;
+                let ab = Fq2([a0 * b0 - a1 * b1, a0 * b1 + a1 * b0]);
+                assert_eq!(a.mul_wide(b).reduce(), ab);
+                assert_eq!(a.mul_wide(b).mul_by_nonres().reduce(), ab.mul_by_nonres());
+                assert_eq!(a.mul_wide(b).double().reduce(), ab.double());
+                assert_eq!((a.mul_wide(b) - b.square_wide()).reduce(), ab - b * b);
+            }
+            let [a0, a1] = a.0;
+            let aa = Fq2([a0.square() - a1.square(), (a0 * a1).double()]);
+            assert_eq!(a.square_wide().reduce(), aa);
+        }
+    }
+
+    #[test]
+    fn fq2_wide_bounds_test() {
+        let top = Fq2Wide([TOP, TOP]);
+        let t = Fq2([reduce(TOP), reduce(TOP)]);
+        assert_eq!(top.reduce(), t);
+        // nine top values and their carries through the non-residue
+        assert_eq!(top.mul_by_nonres().reduce(), t.mul_by_nonres());
+        assert_eq!(top.double().reduce(), t.double());
+        assert_eq!((Fq2Wide::default() - top).reduce(), -t);
+        assert_eq!((Fq2Wide::default() - top + top).0.map(|c| c.0), [[0; 8]; 2]);
+    }
+}
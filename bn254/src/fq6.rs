@@ -1,10 +1,12 @@
-use crate::fq2::Fq2;
+use crate::fq2::{Fq2, Fq2Wide};
 use crate::params::{FROBENIUS_COEFF_FQ6_C1, FROBENIUS_COEFF_FQ6_C2};
-use core::ops::{Add, Mul, Sub, SubAssign};
-use std::ops::Neg;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub, SubAssign};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) struct Fq6(pub(crate) [Fq2; 3]);
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fq6(pub(crate) [Fq2; 3]);
 
 impl Fq6 {
     pub const fn zero() -> Self {
@@ -15,30 +17,75 @@ impl Fq6 {
         Self([Fq2::one(), Fq2::zero(), Fq2::zero()])
     }
 
+    pub const fn from_u64(val: u64) -> Self {
+        Self([Fq2::from_u64(val), Fq2::zero(), Fq2::zero()])
+    }
+
+    /// Parse a base field element as `Fq::from_str_radix` does and embed it
+    /// as `c0.c0`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        Fq2::from_str_radix(s, radix).map(|c0| Self([c0, Fq2::zero(), Fq2::zero()]))
+    }
+
+    pub fn random<R: RngCore>(rand: &mut R) -> Self {
+        Self([Fq2::random(rand), Fq2::random(rand), Fq2::random(rand)])
+    }
+
+    /// Decode `c0 || c1 || c2`, each as in `Fq2::from_bytes_le`.
+    pub fn from_bytes_le(bytes: &[u8; 192]) -> CtOption<Self> {
+        let c0 = Fq2::from_bytes_le(bytes[..64].try_into().unwrap());
+        let c1 = Fq2::from_bytes_le(bytes[64..128].try_into().unwrap());
+        let c2 = Fq2::from_bytes_le(bytes[128..].try_into().unwrap());
+        c0.and_then(|c0| c1.and_then(|c1| c2.map(|c2| Self([c0, c1, c2]))))
+    }
+
+    /// Byte reversal of the little-endian encoding.
+    pub fn from_bytes_be(bytes: &[u8; 192]) -> CtOption<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+
+    pub fn to_bytes_le(self) -> [u8; 192] {
+        let mut bytes = [0; 192];
+        for (chunk, c) in bytes.chunks_exact_mut(64).zip(self.0) {
+            chunk.copy_from_slice(&c.to_bytes_le());
+        }
+        bytes
+    }
+
+    pub fn to_bytes_be(self) -> [u8; 192] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
     pub(crate) fn double(self) -> Self {
         Self([self.0[0].double(), self.0[1].double(), self.0[2].double()])
     }
 
     pub fn square(self) -> Self {
-        let s0 = self.0[0].square();
-        let ab = self.0[0] * self.0[1];
-        let s1 = ab.double();
-        let mut s2 = self.0[0];
-        s2 -= self.0[1];
-        s2 += self.0[2];
-        s2 = s2.square();
-        let bc = self.0[1] * self.0[2];
-        let s3 = bc.double();
-        let s4 = self.0[2].square();
+        self.square_wide().reduce()
+    }
+
+    /// Chung-Hasan squaring (CH-SQR2), left unreduced.
+    #[inline(always)]
+    pub(crate) fn square_wide(self) -> Fq6Wide {
+        let [a0, a1, a2] = self.0;
+        let s0 = a0.square_wide();
+        let s1 = a0.mul_wide(a1).double();
+        let s2 = (a0 - a1 + a2).square_wide();
+        let s3 = a1.mul_wide(a2).double();
+        let s4 = a2.square_wide();
 
         let c0 = s3.mul_by_nonres() + s0;
         let c1 = s4.mul_by_nonres() + s1;
         let c2 = s1 + s2 + s3 - s0 - s4;
 
-        Self([c0, c1, c2])
+        Fq6Wide([c0, c1, c2])
     }
 
-    pub(crate) fn invert(self) -> Option<Self> {
+    pub fn invert(self) -> CtOption<Self> {
         let c0 = (self.0[1] * self.0[2]).mul_by_nonres();
         let c0 = self.0[0].square() - c0;
 
@@ -54,14 +101,16 @@ impl Fq6 {
         tmp.invert().map(|t| Self([t * c0, t * c1, t * c2]))
     }
 
-    pub(crate) fn mul_by_01(&self, c0: Fq2, c1: Fq2) -> Self {
-        let a_a = self.0[0] * c0;
-        let b_b = self.0[1] * c1;
-        let t1 = ((self.0[1] + self.0[2]) * c1 - b_b).mul_by_nonres() + a_a;
-        let t2 = (c0 + c1) * (self.0[0] + self.0[1]) - a_a - b_b;
-        let t3 = (self.0[0] + self.0[2]) * c0 - a_a + b_b;
+    /// Product with the sparse `c0 + c1 * v`, left unreduced.
+    #[inline(always)]
+    pub(crate) fn mul_by_01_wide(&self, c0: Fq2, c1: Fq2) -> Fq6Wide {
+        let a_a = self.0[0].mul_wide(c0);
+        let b_b = self.0[1].mul_wide(c1);
+        let t1 = ((self.0[1] + self.0[2]).mul_wide(c1) - b_b).mul_by_nonres() + a_a;
+        let t2 = (c0 + c1).mul_wide(self.0[0] + self.0[1]) - a_a - b_b;
+        let t3 = (self.0[0] + self.0[2]).mul_wide(c0) - a_a + b_b;
 
-        Self([t1, t2, t3])
+        Fq6Wide([t1, t2, t3])
     }
 
     pub(crate) fn mul_by_nonres(self) -> Self {
@@ -85,6 +134,68 @@ impl Fq6 {
     }
 }
 
+/// An unreduced `Fq6` product, see `FqWide`.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Fq6Wide(pub(crate) [Fq2Wide; 3]);
+
+impl Fq6Wide {
+    #[inline(always)]
+    pub(crate) fn mul_by_nonres(self) -> Self {
+        Self([self.0[2].mul_by_nonres(), self.0[0], self.0[1]])
+    }
+
+    #[inline(always)]
+    pub(crate) fn reduce(self) -> Fq6 {
+        Fq6([self.0[0].reduce(), self.0[1].reduce(), self.0[2].reduce()])
+    }
+}
+
+impl fmt::LowerHex for Fq6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.to_bytes_be() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Fq6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}) + ({}) * v + ({}) * v^2",
+            self.0[0], self.0[1], self.0[2]
+        )
+    }
+}
+
+impl ConstantTimeEq for Fq6 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[0].ct_eq(&other.0[0]) & self.0[1].ct_eq(&other.0[1]) & self.0[2].ct_eq(&other.0[2])
+    }
+}
+
+impl ConditionallySelectable for Fq6 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self([
+            Fq2::conditional_select(&a.0[0], &b.0[0], choice),
+            Fq2::conditional_select(&a.0[1], &b.0[1], choice),
+            Fq2::conditional_select(&a.0[2], &b.0[2], choice),
+        ])
+    }
+}
+
+impl PartialEq for Fq6 {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fq6 {}
+
 impl Add for Fq6 {
     type Output = Self;
 
@@ -127,6 +238,11 @@ impl Mul<Fq6> for Fq6 {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self {
+        // Karatsuba on top of reduced Fq2 products. Keeping them wide halves
+        // the Fq2 reductions, but the sums and the non-residue multiplication
+        // then run on eight limbs, which measured about a third slower on the
+        // 64-bit backend, here and for Fq12. Only the sparse products and the
+        // squarings, with fewer such sums, stay wide.
         let a_a = self.0[0] * rhs.0[0];
         let b_b = self.0[1] * rhs.0[1];
         let c_c = self.0[2] * rhs.0[2];
@@ -165,3 +281,89 @@ impl Mul<Fq6> for Fq6 {
         Self([t1, t2, t3])
     }
 }
+
+impl Add for Fq6Wide {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] + rhs.0[0],
+            self.0[1] + rhs.0[1],
+            self.0[2] + rhs.0[2],
+        ])
+    }
+}
+
+impl Sub for Fq6Wide {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] - rhs.0[0],
+            self.0[1] - rhs.0[1],
+            self.0[2] - rhs.0[2],
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fq::tests::{edge_elements, reduce, TOP};
+    use crate::fq::Fq;
+
+    /// Elements with coefficients at the edges of `[0, q)`.
+    fn edge_elements6() -> impl Iterator<Item = Fq6> {
+        let e = edge_elements();
+        e.into_iter().flat_map(move |x| {
+            e.into_iter()
+                .map(move |y| Fq6([Fq2([x, y]), Fq2([y, x]), Fq2([x, x])]))
+        })
+    }
+
+    /// Schoolbook product with `v^3 = 9 + u`.
+    fn schoolbook(a: Fq6, b: Fq6) -> Fq6 {
+        let [a0, a1, a2] = a.0;
+        let [b0, b1, b2] = b.0;
+        Fq6([
+            a0 * b0 + (a1 * b2 + a2 * b1).mul_by_nonres(),
+            a0 * b1 + a1 * b0 + (a2 * b2).mul_by_nonres(),
+            a0 * b2 + a1 * b1 + a2 * b0,
+        ])
+    }
+
+    #[test]
+    fn fq6_wide_products_test() {
+        for a in edge_elements6() {
+            for b in edge_elements6() {
+                assert_eq!(a * b, schoolbook(a, b));
+
+                let [b0, b1, _] = b.0;
+                let sparse = Fq6([b0, b1, Fq2::zero()]);
+                assert_eq!(a.mul_by_01_wide(b0, b1).reduce(), schoolbook(a, sparse));
+                assert_eq!(
+                    a.mul_by_01_wide(b0, b1).mul_by_nonres().reduce(),
+                    schoolbook(a, sparse).mul_by_nonres()
+                );
+            }
+            assert_eq!(a.square_wide().reduce(), schoolbook(a, a));
+        }
+    }
+
+    #[test]
+    fn fq6_wide_bounds_test() {
+        let top = Fq6Wide([Fq2Wide([TOP, TOP]); 3]);
+        let t = Fq2([reduce(TOP), reduce(TOP)]);
+        let t = Fq6([t; 3]);
+        assert_eq!(top.reduce(), t);
+        assert_eq!(top.mul_by_nonres().reduce(), t.mul_by_nonres());
+        assert_eq!((Fq6Wide::default() - top).reduce(), -t);
+        assert_eq!((top + top - top).reduce(), t);
+
+        let minus_one = -Fq6([Fq2([Fq::one(), Fq::one()]); 3]);
+        let m = minus_one.square_wide();
+        assert_eq!((m - top).reduce(), schoolbook(minus_one, minus_one) - t);
+    }
+}
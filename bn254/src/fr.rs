@@ -1,7 +1,17 @@
-use core::ops::Mul;
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+use zeroize::Zeroize;
 
-use crate::limbs::{mul, random_limbs};
+use crate::limbs::{
+    add, conditional_select, const_mul, ct_eq, ct_is_zero, ct_lt, from_le_bytes, from_str_radix,
+    mont, mul, neg, pow, random_limbs, square, sub, to_le_bytes,
+};
+use crate::safegcd;
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
 
 pub(crate) const MODULUS: [u64; 4] = [
     0x43e1f593f0000001,
@@ -37,12 +47,291 @@ pub(crate) const R3: [u64; 4] = [
 /// INV = -(r^{-1} mod 2^64) mod 2^64
 pub const INV: u64 = 0xc2e1f593efffffff;
 
-pub struct Fr(pub [u64; 4]);
+/// INV62 = r^{-1} mod 2^62
+pub(crate) const INV62: u64 = 0x3d1e0a6c10000001;
+
+/// r - 1 = 2^S * t with t odd
+const S: u32 = 28;
+
+/// (t - 1) / 2
+const T_MINUS_ONE_OVER_TWO: [u64; 4] = [
+    0xcdcb848a1f0fac9f,
+    0x0c0ac2e9419f4243,
+    0x098d014dc2822db4,
+    0x0000000183227397,
+];
+
+/// (r - 1) / 2
+const LEGENDRE_EXP: [u64; 4] = [
+    0xa1f0fac9f8000000,
+    0x9419f4243cdcb848,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+];
+
+/// 5^t, a primitive 2^S-th root of unity, in Montgomery form
+pub(crate) const ROOT_OF_UNITY: Fr = Fr([
+    0x636e735580d13d9c,
+    0xa22bf3742445ffd6,
+    0x56452ac01eb203d8,
+    0x1860ef942963f9e7,
+]);
+
+/// Scalar field element, held in Montgomery form. Conversions to and from
+/// integers and bytes always go through the canonical value.
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Fr(pub(crate) [u64; 4]);
 
 impl Fr {
+    pub const fn zero() -> Self {
+        Self([0; 4])
+    }
+
+    pub const fn one() -> Self {
+        Self(R)
+    }
+
+    pub fn is_zero(self) -> Choice {
+        ct_is_zero(self.0)
+    }
+
+    pub fn square(self) -> Self {
+        Self(square(self.0, MODULUS, INV))
+    }
+
     pub fn random<R: RngCore>(rand: &mut R) -> Self {
         Self(random_limbs(rand, R2, R3, MODULUS, INV))
     }
+
+    pub const fn from_u64(val: u64) -> Self {
+        Self::to_mont_form([val, 0, 0, 0])
+    }
+
+    /// Parse the canonical integer in the given radix, rejecting values not
+    /// below the modulus.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        from_str_radix(s, radix, MODULUS).map(Self::to_mont_form)
+    }
+
+    /// Decode the canonical little-endian integer, rejecting values not
+    /// below the modulus.
+    pub fn from_bytes_le(bytes: &[u8; 32]) -> CtOption<Self> {
+        let raw = from_le_bytes(bytes);
+        CtOption::new(Self::to_mont_form(raw), ct_lt(raw, MODULUS))
+    }
+
+    pub fn from_bytes_be(bytes: &[u8; 32]) -> CtOption<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+
+    pub fn to_bytes_le(self) -> [u8; 32] {
+        to_le_bytes(self.to_raw())
+    }
+
+    /// Big-endian encoding, the layout of an EVM uint256 word
+    pub fn to_bytes_be(self) -> [u8; 32] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    pub(crate) const fn to_mont_form(val: [u64; 4]) -> Self {
+        Self(const_mul(val, R2, MODULUS, INV))
+    }
+
+    /// Constant time inversion by Bernstein–Yang divsteps.
+    pub fn invert(self) -> CtOption<Self> {
+        // (aR)^{-1} = a^{-1}R^{-1}, and a Montgomery multiplication by R^3
+        // brings it back to a^{-1}R
+        let inv = mul(safegcd::invert(self.0, MODULUS, INV62), R3, MODULUS, INV);
+        CtOption::new(Self(inv), !self.is_zero())
+    }
+
+    /// Raise to the power of the little-endian integer `exp`, in time
+    /// independent of both operands.
+    pub fn pow(self, exp: [u64; 4]) -> Self {
+        Self(pow(self.0, exp, R, MODULUS, INV))
+    }
+
+    /// Constant time Tonelli–Shanks square root, adapted from the `ff` crate.
+    pub fn sqrt(self) -> CtOption<Self> {
+        let w = self.pow(T_MINUS_ONE_OVER_TWO);
+
+        let mut v = S;
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v = Choice::from(1);
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Self::one());
+                let squared = Self::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Self::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Self::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Self::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Self::conditional_select(&result, &x, b.ct_eq(&Self::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(&self))
+    }
+
+    /// Legendre symbol: 1 for non-zero squares, -1 for non-squares and 0
+    /// for zero.
+    pub fn legendre(self) -> i8 {
+        let symbol = self.pow(LEGENDRE_EXP);
+        let res = i8::conditional_select(&-1, &1, symbol.ct_eq(&Self::one()));
+        i8::conditional_select(&res, &0, self.is_zero())
+    }
+
+    pub fn is_square(self) -> Choice {
+        !self.pow(LEGENDRE_EXP).ct_eq(&-Self::one())
+    }
+
+    /// Invert every element of `elems` in place with a single field inversion
+    /// (Montgomery's trick). Zero elements are left as zero. `scratch` must
+    /// be at least as long as `elems`.
+    pub fn batch_invert_with_scratch(elems: &mut [Self], scratch: &mut [Self]) {
+        assert!(scratch.len() >= elems.len());
+        // scratch[i] holds the product of the non-zero elems[..i]
+        let mut acc = Self::one();
+        for (elem, prefix) in elems.iter().zip(scratch.iter_mut()) {
+            *prefix = acc;
+            acc = Self::conditional_select(&(acc * *elem), &acc, elem.is_zero());
+        }
+
+        // acc is a product of non-zero elements, so it is invertible
+        let mut acc = acc.invert().unwrap();
+        for (elem, prefix) in elems.iter_mut().zip(scratch.iter()).rev() {
+            let is_zero = elem.is_zero();
+            let inv = acc * *prefix;
+            acc = Self::conditional_select(&(acc * *elem), &acc, is_zero);
+            *elem = Self::conditional_select(&inv, elem, is_zero);
+        }
+    }
+
+    /// `batch_invert_with_scratch` with the scratch space on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn batch_invert(elems: &mut [Self]) {
+        let mut scratch = vec![Self::zero(); elems.len()];
+        Self::batch_invert_with_scratch(elems, &mut scratch);
+    }
+
+    /// Reduce a 512-bit little-endian integer `lo + hi * 2^256` mod r. Each
+    /// half is below 2^256, so a Montgomery multiplication by `R^2` and `R^3`
+    /// brings it into Montgomery form.
+    pub(crate) fn from_u512(lo: [u64; 4], hi: [u64; 4]) -> Self {
+        Self(mul(lo, R2, MODULUS, INV)) + Self(mul(hi, R3, MODULUS, INV))
+    }
+
+    /// Convert from Montgomery form to the canonical integer limbs.
+    pub(crate) const fn to_raw(self) -> [u64; 4] {
+        mont(
+            [self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0],
+            MODULUS,
+            INV,
+        )
+    }
+}
+
+impl fmt::LowerHex for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.to_bytes_be() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+impl fmt::Debug for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+impl ConstantTimeEq for Fr {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ct_eq(self.0, other.0)
+    }
+}
+
+impl ConditionallySelectable for Fr {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(conditional_select(a.0, b.0, choice))
+    }
+}
+
+impl PartialEq for Fr {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fr {}
+
+impl Zeroize for Fr {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Add for Fr {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(add(self.0, rhs.0, MODULUS))
+    }
+}
+
+impl AddAssign for Fr {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Fr {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(sub(self.0, rhs.0, MODULUS))
+    }
+}
+
+impl SubAssign for Fr {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Fr {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(neg(self.0, MODULUS))
+    }
 }
 
 impl Mul<Fr> for Fr {
@@ -52,3 +341,9 @@ impl Mul<Fr> for Fr {
         Self(mul(self.0, rhs.0, MODULUS, INV))
     }
 }
+
+impl MulAssign for Fr {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
@@ -1,10 +1,37 @@
-use core::ops::Neg;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+use zeroize::Zeroize;
 
+use crate::curve::{affine_batch_impl, projective_table_impl};
 use crate::fq::Fq;
+use crate::fr::Fr;
+use crate::glv;
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
 
 pub(crate) const G1_GENERATOR_X: Fq = Fq::one();
 pub(crate) const G1_GENERATOR_Y: Fq = Fq::to_mont_form([2, 0, 0, 0]);
 
+/// Curve parameter b = 3
+pub(crate) const G1_PARAM_B: Fq = Fq::to_mont_form([3, 0, 0, 0]);
+
+/// Flags in the top two bits of a compressed encoding, which q < 2^254
+/// leaves free: the lexicographically smallest or largest y, or the identity
+pub(crate) const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+pub(crate) const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+pub(crate) const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+pub(crate) const COMPRESSED_FLAGS: u8 = 0b11 << 6;
+
+/// Cube root of unity β for which `(x, y) -> (βx, -y)` multiplies by the
+/// GLV eigenvalue λ
+const BETA: Fq = Fq::to_mont_form([
+    0x5763473177fffffe,
+    0xd4f263f1acdb5c4f,
+    0x59e26bcea0d48bac,
+    0,
+]);
+
 #[derive(Clone, Copy, Debug)]
 pub struct G1Affine {
     pub(crate) x: Fq,
@@ -13,10 +40,22 @@ pub struct G1Affine {
 }
 
 impl G1Affine {
-    pub(crate) fn is_identity(self) -> bool {
+    pub const fn identity() -> Self {
+        Self {
+            x: Fq::zero(),
+            y: Fq::zero(),
+            is_infinity: true,
+        }
+    }
+
+    pub fn is_identity(self) -> bool {
         self.is_infinity
     }
 
+    pub(crate) fn ct_is_identity(&self) -> Choice {
+        Choice::from(self.is_infinity as u8)
+    }
+
     pub const fn generator() -> Self {
         Self {
             x: G1_GENERATOR_X,
@@ -24,8 +63,123 @@ impl G1Affine {
             is_infinity: false,
         }
     }
+
+    /// Affine x coordinate, zero for the identity.
+    pub fn x(&self) -> Fq {
+        self.x
+    }
+
+    /// Affine y coordinate, zero for the identity.
+    pub fn y(&self) -> Fq {
+        self.y
+    }
+
+    pub fn is_on_curve(self) -> bool {
+        self.is_infinity || self.y.square() == self.x.square() * self.x + G1_PARAM_B
+    }
+
+    /// Decode the EIP-196 encoding `x || y`, both big-endian, with all zeros
+    /// for the identity. Fails on non-canonical coordinates and on points
+    /// off the curve; G1 has cofactor one, so no subgroup check is needed.
+    pub fn from_uncompressed(bytes: &[u8; 64]) -> CtOption<Self> {
+        let x = Fq::from_bytes_be(bytes[..32].try_into().unwrap());
+        let y = Fq::from_bytes_be(bytes[32..].try_into().unwrap());
+
+        x.and_then(|x| {
+            y.and_then(|y| {
+                let p = Self {
+                    x,
+                    y,
+                    is_infinity: false,
+                };
+                let p = Self::conditional_select(&p, &Self::identity(), x.is_zero() & y.is_zero());
+                CtOption::new(p, Choice::from(p.is_on_curve() as u8))
+            })
+        })
+    }
+
+    pub fn to_uncompressed(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&self.x.to_bytes_be());
+        bytes[32..].copy_from_slice(&self.y.to_bytes_be());
+        bytes
+    }
+
+    /// Compressed encoding: the big-endian x coordinate with the flags of
+    /// y in its top two bits, and the identity as the infinity flag over
+    /// zeros. This is the layout gnark-crypto uses.
+    pub fn to_compressed(&self) -> [u8; 32] {
+        let mut bytes = self.x.to_bytes_be();
+        let flag = u8::conditional_select(
+            &COMPRESSED_SMALLEST,
+            &COMPRESSED_LARGEST,
+            self.y.is_lexicographically_largest(),
+        );
+        bytes[0] |= u8::conditional_select(&flag, &COMPRESSED_INFINITY, self.ct_is_identity());
+        bytes
+    }
+
+    /// Decode `to_compressed`, rejecting unknown flags, a non-canonical x
+    /// and x coordinates of no point on the curve.
+    pub fn from_compressed(bytes: &[u8; 32]) -> CtOption<Self> {
+        let flags = bytes[0] & COMPRESSED_FLAGS;
+        let mut x_bytes = *bytes;
+        x_bytes[0] &= !COMPRESSED_FLAGS;
+        let is_largest = flags.ct_eq(&COMPRESSED_LARGEST);
+        let is_point = is_largest | flags.ct_eq(&COMPRESSED_SMALLEST);
+        let is_identity = flags.ct_eq(&COMPRESSED_INFINITY) & x_bytes.ct_eq(&[0; 32]);
+
+        let point = Fq::from_bytes_be(&x_bytes).and_then(|x| {
+            (x.square() * x + G1_PARAM_B).sqrt().and_then(|y| {
+                let flip = y.is_lexicographically_largest() ^ is_largest;
+                let p = Self {
+                    x,
+                    y: Fq::conditional_select(&y, &-y, flip),
+                    is_infinity: false,
+                };
+                CtOption::new(p, Choice::from(1))
+            })
+        });
+        let p = Self::conditional_select(
+            &point.unwrap_or(Self::identity()),
+            &Self::identity(),
+            is_identity,
+        );
+        CtOption::new(p, is_identity | (is_point & point.is_some()))
+    }
+}
+
+affine_batch_impl!(G1Affine, G1Projective, Fq);
+
+impl ConstantTimeEq for G1Affine {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (a, b) = (self.ct_is_identity(), other.ct_is_identity());
+        (a & b) | (!a & !b & self.x.ct_eq(&other.x) & self.y.ct_eq(&other.y))
+    }
+}
+
+impl ConditionallySelectable for G1Affine {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: Fq::conditional_select(&a.x, &b.x, choice),
+            y: Fq::conditional_select(&a.y, &b.y, choice),
+            is_infinity: bool::from(Choice::conditional_select(
+                &a.ct_is_identity(),
+                &b.ct_is_identity(),
+                choice,
+            )),
+        }
+    }
+}
+
+impl PartialEq for G1Affine {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
 }
 
+impl Eq for G1Affine {}
+
 impl Neg for G1Affine {
     type Output = Self;
 
@@ -37,3 +191,280 @@ impl Neg for G1Affine {
         }
     }
 }
+
+impl From<G1Projective> for G1Affine {
+    fn from(p: G1Projective) -> Self {
+        let z_inv = p.z.invert().unwrap_or(Fq::zero());
+        let z_inv2 = z_inv.square();
+        let affine = Self {
+            x: p.x * z_inv2,
+            y: p.y * z_inv2 * z_inv,
+            is_infinity: false,
+        };
+
+        Self::conditional_select(&affine, &Self::identity(), p.z.is_zero())
+    }
+}
+
+impl Mul<Fr> for G1Affine {
+    type Output = G1Projective;
+
+    fn mul(self, rhs: Fr) -> G1Projective {
+        G1Projective::from(self) * rhs
+    }
+}
+
+/// G1 point in Jacobian coordinates, `(x, y, z)` represents `(x/z^2, y/z^3)`.
+#[derive(Clone, Copy, Debug)]
+pub struct G1Projective {
+    pub(crate) x: Fq,
+    pub(crate) y: Fq,
+    pub(crate) z: Fq,
+}
+
+impl G1Projective {
+    pub const fn identity() -> Self {
+        Self {
+            x: Fq::zero(),
+            y: Fq::one(),
+            z: Fq::zero(),
+        }
+    }
+
+    pub fn is_identity(self) -> bool {
+        self.z.is_zero().into()
+    }
+
+    pub const fn generator() -> Self {
+        Self {
+            x: G1_GENERATOR_X,
+            y: G1_GENERATOR_Y,
+            z: Fq::one(),
+        }
+    }
+
+    pub fn double(self) -> Self {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#doubling-dbl-2009-l
+        // z = 0 stays z = 0, so the identity needs no special case
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = ((self.x + b).square() - a - c).double();
+        let e = a.double() + a;
+        let f = e.square();
+        let x = f - d.double();
+        let y = e * (d - x) - c.double().double().double();
+        let z = (self.y * self.z).double();
+
+        Self { x, y, z }
+    }
+
+    /// `(x, y) -> (βx, -y)`, the same as multiplying by λ.
+    fn endomorphism(self) -> Self {
+        Self {
+            x: self.x * BETA,
+            y: -self.y,
+            z: self.z,
+        }
+    }
+}
+
+projective_table_impl!(G1Projective);
+
+impl ConstantTimeEq for G1Projective {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (a, b) = (self.z.is_zero(), other.z.is_zero());
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let x = (self.x * z2z2).ct_eq(&(other.x * z1z1));
+        let y = (self.y * z2z2 * other.z).ct_eq(&(other.y * z1z1 * self.z));
+
+        (a & b) | (!a & !b & x & y)
+    }
+}
+
+impl ConditionallySelectable for G1Projective {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: Fq::conditional_select(&a.x, &b.x, choice),
+            y: Fq::conditional_select(&a.y, &b.y, choice),
+            z: Fq::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl PartialEq for G1Projective {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for G1Projective {}
+
+impl From<G1Affine> for G1Projective {
+    fn from(affine: G1Affine) -> Self {
+        let projective = Self {
+            x: affine.x,
+            y: affine.y,
+            z: Fq::one(),
+        };
+
+        Self::conditional_select(&projective, &Self::identity(), affine.ct_is_identity())
+    }
+}
+
+impl Add for G1Projective {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#addition-add-2007-bl
+        // The exceptional cases are resolved with selects rather than early
+        // returns so the running time does not reveal them.
+        let z1z1 = self.z.square();
+        let z2z2 = rhs.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = rhs.x * z1z1;
+        let s1 = self.y * rhs.z * z2z2;
+        let s2 = rhs.y * self.z * z1z1;
+
+        let h = u2 - u1;
+        let i = h.double().square();
+        let j = h * i;
+        let r = (s2 - s1).double();
+        let v = u1 * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (s1 * j).double();
+        // h = 0 yields z = 0, which is already the answer for P + (-P)
+        let z = ((self.z + rhs.z).square() - z1z1 - z2z2) * h;
+
+        let sum = Self { x, y, z };
+        let sum = Self::conditional_select(&sum, &self.double(), h.is_zero() & r.is_zero());
+        let sum = Self::conditional_select(&sum, &rhs, self.z.is_zero());
+        Self::conditional_select(&sum, &self, rhs.z.is_zero())
+    }
+}
+
+impl Add<G1Affine> for G1Projective {
+    type Output = Self;
+
+    fn add(self, rhs: G1Affine) -> Self {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#addition-madd-2007-bl
+        // with the exceptional cases resolved by selects as in `add`
+        let z1z1 = self.z.square();
+        let u2 = rhs.x * z1z1;
+        let s2 = rhs.y * self.z * z1z1;
+
+        let h = u2 - self.x;
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h * i;
+        let r = (s2 - self.y).double();
+        let v = self.x * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (self.y * j).double();
+        // h = 0 yields z = 0, which is already the answer for P + (-P)
+        let z = (self.z + h).square() - z1z1 - hh;
+
+        let sum = Self { x, y, z };
+        let sum = Self::conditional_select(&sum, &self.double(), h.is_zero() & r.is_zero());
+        let sum = Self::conditional_select(&sum, &Self::from(rhs), self.z.is_zero());
+        Self::conditional_select(&sum, &self, rhs.ct_is_identity())
+    }
+}
+
+impl AddAssign for G1Projective {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for G1Projective {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl SubAssign for G1Projective {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl AddAssign<G1Affine> for G1Projective {
+    fn add_assign(&mut self, rhs: G1Affine) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<G1Affine> for G1Projective {
+    type Output = Self;
+
+    fn sub(self, rhs: G1Affine) -> Self {
+        self + -rhs
+    }
+}
+
+impl SubAssign<G1Affine> for G1Projective {
+    fn sub_assign(&mut self, rhs: G1Affine) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for G1Projective {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl Mul<Fr> for G1Projective {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, mut rhs: Fr) -> Self {
+        // kP = k1 P + k2 λP with k1, k2 below 2^127, both walked at once over
+        // regular signed windows so the doublings are shared and halved. The
+        // recoding needs odd halves, so an even half is rounded up and the
+        // extra point taken off at the end by a select.
+        let [(neg1, mut k1), (neg2, mut k2)] = glv::decompose(&rhs);
+        let table1 = self.odd_multiples();
+        let table2 = table1.map(Self::endomorphism);
+        let mut digits1 = glv::recode::<{ glv::GLV_DIGITS }>(k1 | 1);
+        let mut digits2 = glv::recode::<{ glv::GLV_DIGITS }>(k2 | 1);
+
+        let mut res = Self::identity();
+        for i in (0..glv::GLV_DIGITS).rev() {
+            for _ in 0..glv::WINDOW {
+                res = res.double();
+            }
+            res += Self::lookup(&table1, digits1[i], neg1);
+            res += Self::lookup(&table2, digits2[i], neg2);
+        }
+
+        let p1 = Self::conditional_select(&-self, &self, neg1);
+        let p2 = self.endomorphism();
+        let p2 = Self::conditional_select(&-p2, &p2, neg2);
+        let res = Self::conditional_select(&res, &(res + p1), Choice::from((!k1 & 1) as u8));
+        let res = Self::conditional_select(&res, &(res + p2), Choice::from((!k2 & 1) as u8));
+
+        rhs.zeroize();
+        k1.zeroize();
+        k2.zeroize();
+        digits1.zeroize();
+        digits2.zeroize();
+        res
+    }
+}
+
+impl MulAssign<Fr> for G1Projective {
+    fn mul_assign(&mut self, rhs: Fr) {
+        *self = *self * rhs;
+    }
+}
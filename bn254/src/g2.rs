@@ -1,14 +1,23 @@
+use crate::curve::{affine_batch_impl, projective_table_impl};
 use crate::fq::Fq;
 use crate::fq2::Fq2;
+use crate::fr::Fr;
+use crate::g1::{COMPRESSED_FLAGS, COMPRESSED_INFINITY, COMPRESSED_LARGEST, COMPRESSED_SMALLEST};
+use crate::glv;
 use crate::pairing::{SIX_U_PLUS_2_NAF, XI_TO_Q_MINUS_1_OVER_2};
-use crate::params::FROBENIUS_COEFF_FQ6_C1;
+use crate::params::{BN_6X2, FROBENIUS_COEFF_FQ6_C1};
 
-use core::ops::Neg;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+use zeroize::Zeroize;
 
 #[derive(Clone, Copy, Debug)]
 pub struct G2Affine {
-    x: Fq2,
-    y: Fq2,
+    pub(crate) x: Fq2,
+    pub(crate) y: Fq2,
     is_infinity: bool,
 }
 
@@ -42,11 +51,39 @@ pub(crate) const G2_GENERATOR_Y: Fq2 = Fq2([
     ]),
 ]);
 
+/// Twisted curve parameter b' = 3 / (9 + u)
+pub(crate) const G2_PARAM_B: Fq2 = Fq2([
+    Fq::to_mont_form([
+        0x3267e6dc24a138e5,
+        0xb5b4c5e559dbefa3,
+        0x81be18991be06ac3,
+        0x2b149d40ceb8aaae,
+    ]),
+    Fq::to_mont_form([
+        0xe4a2bd0685c315d2,
+        0xa74fa084e52d1852,
+        0xcd2cafadeed8fdf4,
+        0x009713b03af0fed4,
+    ]),
+]);
+
 impl G2Affine {
-    fn is_identity(self) -> bool {
+    pub const fn identity() -> Self {
+        Self {
+            x: Fq2::zero(),
+            y: Fq2::zero(),
+            is_infinity: true,
+        }
+    }
+
+    pub fn is_identity(self) -> bool {
         self.is_infinity
     }
 
+    pub(crate) fn ct_is_identity(&self) -> Choice {
+        Choice::from(self.is_infinity as u8)
+    }
+
     pub const fn generator() -> Self {
         Self {
             x: G2_GENERATOR_X,
@@ -54,8 +91,151 @@ impl G2Affine {
             is_infinity: false,
         }
     }
+
+    /// Affine x coordinate, zero for the identity.
+    pub fn x(&self) -> Fq2 {
+        self.x
+    }
+
+    /// Affine y coordinate, zero for the identity.
+    pub fn y(&self) -> Fq2 {
+        self.y
+    }
+
+    /// The untwist-Frobenius-twist endomorphism ψ, which acts on the order
+    /// r subgroup as multiplication by q.
+    pub fn psi(&self) -> Self {
+        Self {
+            x: self.x.frobenius_map() * FROBENIUS_COEFF_FQ6_C1[1],
+            y: self.y.frobenius_map() * XI_TO_Q_MINUS_1_OVER_2,
+            is_infinity: self.is_infinity,
+        }
+    }
+
+    pub fn is_on_curve(self) -> bool {
+        self.is_infinity || self.y.square() == self.x.square() * self.x + G2_PARAM_B
+    }
+
+    /// Decode the EIP-197 encoding `x || y`, each coordinate as in
+    /// `Fq2::to_bytes_be`, with all zeros for the identity. Fails on
+    /// non-canonical coordinates, on points off the twist and on points
+    /// outside the order r subgroup.
+    pub fn from_uncompressed(bytes: &[u8; 128]) -> CtOption<Self> {
+        let x = Fq2::from_bytes_be(bytes[..64].try_into().unwrap());
+        let y = Fq2::from_bytes_be(bytes[64..].try_into().unwrap());
+
+        x.and_then(|x| {
+            y.and_then(|y| {
+                let p = Self {
+                    x,
+                    y,
+                    is_infinity: false,
+                };
+                let p = Self::conditional_select(&p, &Self::identity(), x.is_zero() & y.is_zero());
+                let is_valid = Choice::from(p.is_on_curve() as u8) & p.is_torsion_free();
+                CtOption::new(p, is_valid)
+            })
+        })
+    }
+
+    pub fn to_uncompressed(&self) -> [u8; 128] {
+        let mut bytes = [0; 128];
+        bytes[..64].copy_from_slice(&self.x.to_bytes_be());
+        bytes[64..].copy_from_slice(&self.y.to_bytes_be());
+        bytes
+    }
+
+    /// Compressed encoding: `x` as in `Fq2::to_bytes_be` with the flags of
+    /// `G1Affine::to_compressed` in its top two bits, y ordered on
+    /// `(c1, c0)`.
+    pub fn to_compressed(&self) -> [u8; 64] {
+        let mut bytes = self.x.to_bytes_be();
+        let flag = u8::conditional_select(
+            &COMPRESSED_SMALLEST,
+            &COMPRESSED_LARGEST,
+            self.y.is_lexicographically_largest(),
+        );
+        bytes[0] |= u8::conditional_select(&flag, &COMPRESSED_INFINITY, self.ct_is_identity());
+        bytes
+    }
+
+    /// Decode `to_compressed`, rejecting unknown flags, a non-canonical x,
+    /// x coordinates of no point on the twist and points outside the order
+    /// r subgroup.
+    pub fn from_compressed(bytes: &[u8; 64]) -> CtOption<Self> {
+        let flags = bytes[0] & COMPRESSED_FLAGS;
+        let mut x_bytes = *bytes;
+        x_bytes[0] &= !COMPRESSED_FLAGS;
+        let is_largest = flags.ct_eq(&COMPRESSED_LARGEST);
+        let is_point = is_largest | flags.ct_eq(&COMPRESSED_SMALLEST);
+        let is_identity = flags.ct_eq(&COMPRESSED_INFINITY) & x_bytes.ct_eq(&[0; 64]);
+
+        let point = Fq2::from_bytes_be(&x_bytes).and_then(|x| {
+            (x.square() * x + G2_PARAM_B).sqrt().and_then(|y| {
+                let flip = y.is_lexicographically_largest() ^ is_largest;
+                let p = Self {
+                    x,
+                    y: Fq2::conditional_select(&y, &-y, flip),
+                    is_infinity: false,
+                };
+                CtOption::new(p, p.is_torsion_free())
+            })
+        });
+        let p = Self::conditional_select(
+            &point.unwrap_or(Self::identity()),
+            &Self::identity(),
+            is_identity,
+        );
+        CtOption::new(p, is_identity | (is_point & point.is_some()))
+    }
+
+    /// Check `ψ(P) = [6x^2]P`, which on the twist holds exactly on the order
+    /// r subgroup. Unlike G1, the twist has points outside it. The double and add runs over the bits of a public
+    /// constant, so branching on them leaks nothing about `P`.
+    fn is_torsion_free(&self) -> Choice {
+        let p = G2Projective::from(*self);
+        let mut acc = G2Projective::identity();
+        for i in (0..u128::BITS - BN_6X2.leading_zeros()).rev() {
+            acc = acc.double();
+            if (BN_6X2 >> i) & 1 == 1 {
+                acc += p;
+            }
+        }
+        acc.ct_eq(&p.psi())
+    }
+}
+
+affine_batch_impl!(G2Affine, G2Projective, Fq2);
+
+impl ConstantTimeEq for G2Affine {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (a, b) = (self.ct_is_identity(), other.ct_is_identity());
+        (a & b) | (!a & !b & self.x.ct_eq(&other.x) & self.y.ct_eq(&other.y))
+    }
+}
+
+impl ConditionallySelectable for G2Affine {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: Fq2::conditional_select(&a.x, &b.x, choice),
+            y: Fq2::conditional_select(&a.y, &b.y, choice),
+            is_infinity: bool::from(Choice::conditional_select(
+                &a.ct_is_identity(),
+                &b.ct_is_identity(),
+                choice,
+            )),
+        }
+    }
+}
+
+impl PartialEq for G2Affine {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
 }
 
+impl Eq for G2Affine {}
+
 impl Neg for G2Affine {
     type Output = Self;
 
@@ -68,13 +248,73 @@ impl Neg for G2Affine {
     }
 }
 
-struct G2Projective {
-    x: Fq2,
-    y: Fq2,
-    z: Fq2,
+impl From<G2Projective> for G2Affine {
+    fn from(p: G2Projective) -> Self {
+        let z_inv = p.z.invert().unwrap_or(Fq2::zero());
+        let z_inv2 = z_inv.square();
+        let affine = Self {
+            x: p.x * z_inv2,
+            y: p.y * z_inv2 * z_inv,
+            is_infinity: false,
+        };
+
+        Self::conditional_select(&affine, &Self::identity(), p.z.is_zero())
+    }
+}
+
+impl Mul<Fr> for G2Affine {
+    type Output = G2Projective;
+
+    fn mul(self, rhs: Fr) -> G2Projective {
+        G2Projective::from(self) * rhs
+    }
+}
+
+/// G2 point in Jacobian coordinates, `(x, y, z)` represents `(x/z^2, y/z^3)`.
+#[derive(Clone, Copy, Debug)]
+pub struct G2Projective {
+    pub(crate) x: Fq2,
+    pub(crate) y: Fq2,
+    pub(crate) z: Fq2,
 }
 
 impl G2Projective {
+    pub const fn identity() -> Self {
+        Self {
+            x: Fq2::zero(),
+            y: Fq2::one(),
+            z: Fq2::zero(),
+        }
+    }
+
+    pub fn is_identity(self) -> bool {
+        self.z.is_zero().into()
+    }
+
+    pub const fn generator() -> Self {
+        Self {
+            x: G2_GENERATOR_X,
+            y: G2_GENERATOR_Y,
+            z: Fq2::one(),
+        }
+    }
+
+    pub fn double(self) -> Self {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#doubling-dbl-2009-l
+        // z = 0 stays z = 0, so the identity needs no special case
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = ((self.x + b).square() - a - c).double();
+        let e = a.double() + a;
+        let f = e.square();
+        let x = f - d.double();
+        let y = e * (d - x) - c.double().double().double();
+        let z = (self.y * self.z).double();
+
+        Self { x, y, z }
+    }
+
     pub(crate) fn double_eval(&mut self) -> PairingCoeff {
         // Adaptation of Algorithm 26, https://eprint.iacr.org/2010/354.pdf
         let tmp0 = self.x.square();
@@ -127,37 +367,248 @@ impl G2Projective {
 
         PairingCoeff(t10, t1, t9)
     }
+
+    /// `ψ` on Jacobian coordinates, where the Frobenius of `z` keeps the
+    /// ratios.
+    fn psi(self) -> Self {
+        Self {
+            x: self.x.frobenius_map() * FROBENIUS_COEFF_FQ6_C1[1],
+            y: self.y.frobenius_map() * XI_TO_Q_MINUS_1_OVER_2,
+            z: self.z.frobenius_map(),
+        }
+    }
 }
 
-impl From<G2Affine> for G2Projective {
-    fn from(affine: G2Affine) -> Self {
-        if affine.is_identity() {
-            Self {
-                x: Fq2::zero(),
-                y: Fq2::one(),
-                z: Fq2::zero(),
+projective_table_impl!(G2Projective);
+
+impl ConstantTimeEq for G2Projective {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (a, b) = (self.z.is_zero(), other.z.is_zero());
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let x = (self.x * z2z2).ct_eq(&(other.x * z1z1));
+        let y = (self.y * z2z2 * other.z).ct_eq(&(other.y * z1z1 * self.z));
+
+        (a & b) | (!a & !b & x & y)
+    }
+}
+
+impl ConditionallySelectable for G2Projective {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: Fq2::conditional_select(&a.x, &b.x, choice),
+            y: Fq2::conditional_select(&a.y, &b.y, choice),
+            z: Fq2::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl PartialEq for G2Projective {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for G2Projective {}
+
+impl Add for G2Projective {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#addition-add-2007-bl
+        // The exceptional cases are resolved with selects rather than early
+        // returns so the running time does not reveal them.
+        let z1z1 = self.z.square();
+        let z2z2 = rhs.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = rhs.x * z1z1;
+        let s1 = self.y * rhs.z * z2z2;
+        let s2 = rhs.y * self.z * z1z1;
+
+        let h = u2 - u1;
+        let i = h.double().square();
+        let j = h * i;
+        let r = (s2 - s1).double();
+        let v = u1 * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (s1 * j).double();
+        // h = 0 yields z = 0, which is already the answer for P + (-P)
+        let z = ((self.z + rhs.z).square() - z1z1 - z2z2) * h;
+
+        let sum = Self { x, y, z };
+        let sum = Self::conditional_select(&sum, &self.double(), h.is_zero() & r.is_zero());
+        let sum = Self::conditional_select(&sum, &rhs, self.z.is_zero());
+        Self::conditional_select(&sum, &self, rhs.z.is_zero())
+    }
+}
+
+impl Add<G2Affine> for G2Projective {
+    type Output = Self;
+
+    fn add(self, rhs: G2Affine) -> Self {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#addition-madd-2007-bl
+        // with the exceptional cases resolved by selects as in `add`
+        let z1z1 = self.z.square();
+        let u2 = rhs.x * z1z1;
+        let s2 = rhs.y * self.z * z1z1;
+
+        let h = u2 - self.x;
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h * i;
+        let r = (s2 - self.y).double();
+        let v = self.x * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (self.y * j).double();
+        // h = 0 yields z = 0, which is already the answer for P + (-P)
+        let z = (self.z + h).square() - z1z1 - hh;
+
+        let sum = Self { x, y, z };
+        let sum = Self::conditional_select(&sum, &self.double(), h.is_zero() & r.is_zero());
+        let sum = Self::conditional_select(&sum, &Self::from(rhs), self.z.is_zero());
+        Self::conditional_select(&sum, &self, rhs.ct_is_identity())
+    }
+}
+
+impl AddAssign for G2Projective {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for G2Projective {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl SubAssign for G2Projective {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl AddAssign<G2Affine> for G2Projective {
+    fn add_assign(&mut self, rhs: G2Affine) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<G2Affine> for G2Projective {
+    type Output = Self;
+
+    fn sub(self, rhs: G2Affine) -> Self {
+        self + -rhs
+    }
+}
+
+impl SubAssign<G2Affine> for G2Projective {
+    fn sub_assign(&mut self, rhs: G2Affine) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for G2Projective {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl Mul<Fr> for G2Projective {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, mut rhs: Fr) -> Self {
+        // kP = k0 P + k1 ψP + k2 ψ^2 P + k3 ψ^3 P with quarters below 2^66,
+        // walked at once over regular signed windows as on G1. Even quarters
+        // are rounded up to odd and the extra points taken off by selects.
+        let mut quarters = glv::decompose_gls(&rhs);
+        let mut tables = [self.odd_multiples(); 4];
+        for i in 1..4 {
+            tables[i] = tables[i - 1].map(Self::psi);
+        }
+        let mut digits = quarters.map(|(_, k)| glv::recode::<{ glv::GLS_DIGITS }>(k | 1));
+
+        let mut res = Self::identity();
+        for i in (0..glv::GLS_DIGITS).rev() {
+            for _ in 0..glv::WINDOW {
+                res = res.double();
             }
-        } else {
-            Self {
-                x: affine.x,
-                y: affine.y,
-                z: Fq2::one(),
+            for j in 0..4 {
+                res += Self::lookup(&tables[j], digits[j][i], quarters[j].0);
             }
         }
+
+        for (table, (neg, k)) in tables.iter().zip(quarters) {
+            let p = Self::conditional_select(&-table[0], &table[0], neg);
+            res = Self::conditional_select(&res, &(res + p), Choice::from((!k & 1) as u8));
+        }
+
+        rhs.zeroize();
+        quarters.iter_mut().for_each(|(_, k)| k.zeroize());
+        digits.zeroize();
+        res
+    }
+}
+
+impl MulAssign<Fr> for G2Projective {
+    fn mul_assign(&mut self, rhs: Fr) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<G2Affine> for G2Projective {
+    fn from(affine: G2Affine) -> Self {
+        let projective = Self {
+            x: affine.x,
+            y: affine.y,
+            z: Fq2::one(),
+        };
+
+        Self::conditional_select(&projective, &Self::identity(), affine.ct_is_identity())
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct PairingCoeff(pub(crate) Fq2, pub(crate) Fq2, pub(crate) Fq2);
 
+impl PairingCoeff {
+    pub(crate) const fn zero() -> Self {
+        Self(Fq2::zero(), Fq2::zero(), Fq2::zero())
+    }
+}
+
+/// Number of line coefficients of a prepared G2 point. One doubling step per
+/// bit of `6u+2` below the top, one addition step per non-zero NAF digit and
+/// the two Frobenius additions at the end.
+pub(crate) const PAIRING_COEFFS_LEN: usize = 91;
+
+/// G2 point with precomputed Miller loop line coefficients. Coefficients live
+/// in a fixed-size array so preparation and the Miller loop never allocate.
+/// The point itself is kept to encode the coefficients by.
+#[derive(Clone, Debug)]
 pub struct G2PairingAffine {
-    pub(crate) coeffs: Vec<PairingCoeff>,
-    is_infinity: bool,
+    pub(crate) coeffs: [PairingCoeff; PAIRING_COEFFS_LEN],
+    pub(crate) point: G2Affine,
 }
 
 impl G2PairingAffine {
     pub fn is_identity(&self) -> bool {
-        self.is_infinity
+        self.point.is_identity()
+    }
+
+    /// Whether the prepared point is on the twist and in the order r
+    /// subgroup.
+    pub(crate) fn is_well_formed(&self) -> bool {
+        self.point.is_on_curve() && bool::from(self.point.is_torsion_free())
     }
 }
 
@@ -165,47 +616,93 @@ impl From<G2Affine> for G2PairingAffine {
     fn from(g2: G2Affine) -> Self {
         if g2.is_identity() {
             Self {
-                coeffs: vec![],
-                is_infinity: true,
+                coeffs: [PairingCoeff::zero(); PAIRING_COEFFS_LEN],
+                point: g2,
             }
         } else {
-            let mut coeffs = vec![];
+            let mut coeffs = [PairingCoeff::zero(); PAIRING_COEFFS_LEN];
+            let mut index = 0;
+            let mut push = |coeff| {
+                coeffs[index] = coeff;
+                index += 1;
+            };
             let mut g2_projective = G2Projective::from(g2);
             let neg = -g2;
 
             for i in (1..SIX_U_PLUS_2_NAF.len()).rev() {
-                coeffs.push(g2_projective.double_eval());
+                push(g2_projective.double_eval());
                 let x = SIX_U_PLUS_2_NAF[i - 1];
                 match x {
                     1 => {
-                        coeffs.push(g2_projective.add_eval(g2));
+                        push(g2_projective.add_eval(g2));
                     }
                     -1 => {
-                        coeffs.push(g2_projective.add_eval(neg));
+                        push(g2_projective.add_eval(neg));
                     }
                     _ => continue,
                 }
             }
 
-            let mut q = g2;
-
-            q.x.0[1] = -q.x.0[1];
-            q.x *= FROBENIUS_COEFF_FQ6_C1[1];
-
-            q.y.0[1] = -q.y.0[1];
-            q.y *= XI_TO_Q_MINUS_1_OVER_2;
-
-            coeffs.push(g2_projective.add_eval(q));
+            push(g2_projective.add_eval(g2.psi()));
 
             let mut minusq2 = g2;
             minusq2.x *= FROBENIUS_COEFF_FQ6_C1[2];
 
-            coeffs.push(g2_projective.add_eval(minusq2));
+            push(g2_projective.add_eval(minusq2));
+            debug_assert_eq!(index, PAIRING_COEFFS_LEN);
 
-            Self {
-                coeffs,
+            Self { coeffs, point: g2 }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::fr::MODULUS;
+    use crate::limbs::to_bits;
+
+    /// Twist points with small x, which almost never lie in the order r
+    /// subgroup.
+    pub(crate) fn twist_points() -> impl Iterator<Item = G2Affine> + Clone {
+        (1..40).filter_map(|x| {
+            let x = Fq2::from_u64(x);
+            Option::from((x.square() * x + G2_PARAM_B).sqrt()).map(|y| G2Affine {
+                x,
+                y,
                 is_infinity: false,
+            })
+        })
+    }
+
+    #[test]
+    fn g2_torsion_free_test() {
+        let g = G2Projective::generator();
+        let points = [G2Affine::identity(), G2Affine::generator()]
+            .into_iter()
+            .chain(twist_points());
+        // the same points moved by a subgroup point, which keeps them in or
+        // out of the subgroup
+        let moved = points
+            .clone()
+            .zip(1..)
+            .map(|(p, k)| G2Affine::from(g * Fr::from_u64(k).square() + p));
+
+        let mut outside = 0;
+        for p in points.chain(moved) {
+            assert!(p.is_on_curve());
+            // [r]P by plain double and add over the bits of r
+            let q = G2Projective::from(p);
+            let mut r_q = G2Projective::identity();
+            for &bit in to_bits(MODULUS).iter() {
+                r_q = r_q.double();
+                if bit == 1 {
+                    r_q += q;
+                }
             }
+            assert_eq!(bool::from(p.is_torsion_free()), r_q.is_identity(), "{p:?}");
+            outside += usize::from(!r_q.is_identity());
         }
+        assert!(outside > 10);
     }
 }
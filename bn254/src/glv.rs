@@ -0,0 +1,182 @@
+//! Scalar decomposition for the GLV and GLS methods.
+//!
+//! λ = q^2 mod r is a primitive sixth root of unity mod r, the eigenvalue of
+//! the squared Frobenius on Gt. The lattice of `(a, b)` with
+//! `a + b * λ = 0 mod r` has the short basis
+//!
+//! (6x^2 + 4x + 1, -(2x + 1)) and (2x + 1, 6x^2 + 2x)
+//!
+//! from which any scalar splits into two halves of at most 128 bits by
+//! Babai rounding, "Faster Point Multiplication on Elliptic Curves with
+//! Efficient Endomorphisms", https://www.iacr.org/archive/crypto2001/21390189.pdf
+//!
+//! On G2 the untwist-Frobenius-twist map ψ acts as μ = q mod r = 6x^2, and
+//! the four vectors
+//!
+//! (x + 1, x, x, -2x), (2x + 1, -x, -(x + 1), -x),
+//! (2x, 2x + 1, 2x + 1, 2x + 1) and (x - 1, 4x + 2, -2x + 1, x - 1)
+//!
+//! of `(a, b, c, d)` with `a + b * μ + c * μ^2 + d * μ^3 = 0 mod r` split a
+//! scalar into four quarters below 2^66, Galbraith, Lin and Scott,
+//! "Endomorphisms for Faster Elliptic Curve Cryptography on a Large Class of
+//! Curves", https://eprint.iacr.org/2008/194.pdf
+use subtle::{Choice, ConditionallySelectable};
+use zeroize::Zeroize;
+
+use crate::fr::Fr;
+use crate::limbs::ct_lt;
+use crate::math::mac;
+
+/// 6x^2 + 4x + 1
+const A1: Fr = Fr::to_mont_form([0x0be4e1541221250b, 0x6f4d8248eeb859fd, 0, 0]);
+/// 2x + 1
+const A2: Fr = Fr::to_mont_form([0x89d3256894d213e3, 0, 0, 0]);
+/// 6x^2 + 2x
+const B2: Fr = Fr::to_mont_form([0x8211bbeb7d4f1128, 0x6f4d8248eeb859fc, 0, 0]);
+
+/// x
+const X: Fr = Fr::to_mont_form([0x44e992b44a6909f1, 0, 0, 0]);
+/// x + 1
+const X_PLUS_1: Fr = Fr::to_mont_form([0x44e992b44a6909f2, 0, 0, 0]);
+/// x - 1
+const X_MINUS_1: Fr = Fr::to_mont_form([0x44e992b44a6909f0, 0, 0, 0]);
+/// 2x
+const X2: Fr = Fr::to_mont_form([0x89d3256894d213e2, 0, 0, 0]);
+/// 2x - 1
+const X2_MINUS_1: Fr = Fr::to_mont_form([0x89d3256894d213e1, 0, 0, 0]);
+/// 4x + 2
+const X4_PLUS_2: Fr = Fr::to_mont_form([0x13a64ad129a427c6, 1, 0, 0]);
+
+/// floor(2^256 * (6x^2 + 2x) / r)
+const G1: [u64; 4] = [0x7a7bd9d4391eb18d, 0x4ccef014a773d2cf, 2, 0];
+/// floor(2^256 * (2x + 1) / r)
+const G2: [u64; 4] = [0xd91d232ec7e0b3d7, 2, 0, 0];
+
+/// The first row of the inverse of the GLS basis times 2^256, in absolute
+/// value. The last entry is negative.
+const GLS_ROW: [[u64; 4]; 4] = [
+    [0xd0cb46fd51906254, 0xc444fab18d269b9d, 0, 0],
+    [
+        0x001378f5ee78976d,
+        0x22df9f942d7d77c7,
+        0x3d00631561b25729,
+        1,
+    ],
+    [
+        0x36510546a93478ab,
+        0x916fcfca16bebbe4,
+        0x9e80318ab0d92b94,
+        0,
+    ],
+    [0xf7ae23ce89afae7c, 0xc444fab18d269b9a, 0, 0],
+];
+
+/// (r - 1) / 2, the largest canonical value read as non-negative
+const HALF_MODULUS: [u64; 4] = [
+    0xa1f0fac9f8000000,
+    0x9419f4243cdcb848,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+];
+
+/// Width of the signed windows the halves are recoded into.
+pub(crate) const WINDOW: usize = 4;
+
+/// Number of odd multiples a recoded digit selects from.
+pub(crate) const TABLE_LEN: usize = 1 << (WINDOW - 1);
+
+/// Number of signed digits of a recoded GLV half.
+pub(crate) const GLV_DIGITS: usize = 33;
+
+/// Number of signed digits of a recoded GLS quarter.
+pub(crate) const GLS_DIGITS: usize = 18;
+
+/// `floor(a * b / 2^256)`
+const fn mul_shift(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 8];
+    let mut i = 0;
+    while i < 4 {
+        let mut carry = 0;
+        let mut j = 0;
+        while j < 4 {
+            (t[i + j], carry) = mac(t[i + j], a[i], b[j], carry);
+            j += 1;
+        }
+        t[i + 4] = carry;
+        i += 1;
+    }
+    [t[4], t[5], t[6], t[7]]
+}
+
+/// Split `k` into `(k1, k2)` with `k = k1 + k2 * λ mod r`, each returned as
+/// a sign, set when negative, and an absolute value below 2^128. The
+/// scalar may be a secret key, so every named intermediate is wiped.
+pub(crate) fn decompose(k: &Fr) -> [(Choice, u128); 2] {
+    let mut raw = k.to_raw();
+    let mut c1 = Fr::to_mont_form(mul_shift(raw, G1));
+    let mut c2 = Fr::to_mont_form(mul_shift(raw, G2));
+
+    let mut halves = [*k - c1 * A1 - c2 * A2, c1 * A2 - c2 * B2];
+    let res = [signed_abs(&halves[0]), signed_abs(&halves[1])];
+
+    raw.zeroize();
+    c1.zeroize();
+    c2.zeroize();
+    halves.zeroize();
+    res
+}
+
+/// Split `k` into `(k0, k1, k2, k3)` with
+/// `k = k0 + k1 * μ + k2 * μ^2 + k3 * μ^3 mod r`, each returned as a sign,
+/// set when negative, and an absolute value below 2^66, wiping the
+/// intermediates as `decompose` does.
+pub(crate) fn decompose_gls(k: &Fr) -> [(Choice, u128); 4] {
+    let mut raw = k.to_raw();
+    let mut c = GLS_ROW.map(|g| Fr::to_mont_form(mul_shift(raw, g)));
+    let [c0, c1, c2, c3] = c;
+
+    let mut quarters = [
+        *k - c0 * X_PLUS_1 - c1 * A2 - c2 * X2 + c3 * X_MINUS_1,
+        c1 * X - c0 * X - c2 * A2 + c3 * X4_PLUS_2,
+        c1 * X_PLUS_1 - c0 * X - c2 * A2 - c3 * X2_MINUS_1,
+        c0 * X2 + c1 * X - c2 * A2 + c3 * X_MINUS_1,
+    ];
+    let res = [0, 1, 2, 3].map(|i| signed_abs(&quarters[i]));
+
+    raw.zeroize();
+    c.zeroize();
+    quarters.zeroize();
+    res
+}
+
+/// The sign of `k` read as an integer in `(-r/2, r/2)`, and its absolute
+/// value truncated to 128 bits.
+fn signed_abs(k: &Fr) -> (Choice, u128) {
+    let mut raw = k.to_raw();
+    let is_neg = ct_lt(HALF_MODULUS, raw);
+    let mut abs = Fr::conditional_select(k, &-*k, is_neg).to_raw();
+    let res = (is_neg, abs[0] as u128 | (abs[1] as u128) << 64);
+
+    raw.zeroize();
+    abs.zeroize();
+    res
+}
+
+/// Regular signed-window recoding of an odd `k` below `2^(WINDOW * (N - 1))`
+/// and `2^127`, from Joye and Tunstall, "Exponent Recoding and Regular
+/// Exponentiation Algorithms",
+/// https://link.springer.com/chapter/10.1007/978-3-642-02384-2_21
+///
+/// Returns `N` odd digits in `(-2^WINDOW, 2^WINDOW)`, least significant
+/// first, with `k = sum d_i * 2^(WINDOW * i)`. No digit is zero, so a
+/// multiplication walks the same additions for every scalar.
+pub(crate) fn recode<const N: usize>(mut k: u128) -> [i8; N] {
+    let mut digits = [0; N];
+    for d in digits.iter_mut().take(N - 1) {
+        *d = (k & ((1 << (WINDOW + 1)) - 1)) as i8 - (1 << WINDOW);
+        k = k.wrapping_sub(*d as i128 as u128) >> WINDOW;
+    }
+    // the bound on k leaves 1 after the first N - 1 windows
+    digits[N - 1] = k as i8;
+    digits
+}
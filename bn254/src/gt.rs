@@ -1,8 +1,15 @@
-use core::ops::{Add, Neg};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use rand_core::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 use crate::fq12::Fq12;
+use crate::fq2::Fq2;
+use crate::fq6::Fq6;
+use crate::fr::Fr;
+use crate::glv;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Gt(pub Fq12);
 
 impl Gt {
@@ -13,11 +20,111 @@ impl Gt {
     pub fn generator() -> Self {
         Self(Fq12::generator())
     }
+
+    pub fn is_identity(self) -> bool {
+        self.ct_eq(&Self::identity()).into()
+    }
+
+    pub fn random<R: RngCore>(rand: &mut R) -> Self {
+        Self::generator() * Fr::random(rand)
+    }
+
+    /// Check that the element has order r.
+    ///
+    /// Elements of the cyclotomic subgroup satisfy `f^(q^4 - q^2 + 1) = 1`,
+    /// and for those `f^r = 1` is `f^q = f^(6x^2)` since `q = r + 6x^2`,
+    /// which only takes two exponentiations by x.
+    pub fn is_in_subgroup(&self) -> Choice {
+        let f = self.0;
+        let is_cyclotomic = (f.frobenius_maps(4) * f).ct_eq(&f.frobenius_maps(2));
+
+        let fx2 = f.cycolotomic_exp().cycolotomic_exp();
+        let f6x2 = (fx2.cyclotomic_square() * fx2).cyclotomic_square();
+
+        is_cyclotomic & f.frobenius_map().ct_eq(&f6x2)
+    }
+
+    /// Fill `out` with key material derived from the element by the ANSI
+    /// X9.63 KDF over SHA-256, `SHA-256(to_bytes() || counter || info)` for
+    /// a big-endian 32-bit counter starting at 1.
+    pub fn hash_to_bytes(&self, info: &[u8], out: &mut [u8]) {
+        let z = self.to_bytes();
+        for (counter, chunk) in (1u32..).zip(out.chunks_mut(32)) {
+            let block = Sha256::new()
+                .chain_update(z)
+                .chain_update(counter.to_be_bytes())
+                .chain_update(info)
+                .finalize();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    /// Big-endian encoding of the underlying Fq12 element, see
+    /// `Fq12::to_bytes_be`.
+    pub fn to_bytes(&self) -> [u8; 384] {
+        self.0.to_bytes_be()
+    }
+
+    /// Decode `to_bytes`, rejecting non-canonical coefficients and elements
+    /// outside the order r subgroup.
+    pub fn from_bytes(bytes: &[u8; 384]) -> CtOption<Self> {
+        Fq12::from_bytes_be(bytes).and_then(|f| {
+            let gt = Self(f);
+            let is_valid = gt.is_in_subgroup();
+            CtOption::new(gt, is_valid)
+        })
+    }
+
+    /// Compressed encoding in the algebraic torus T2(Fq6).
+    ///
+    /// Gt lies in the elements of norm one over Fq6, which apart from 1 and
+    /// -1 are exactly `(g + w) / (g - w)` for a unique `g` in Fq6, so
+    /// `f = c0 + c1 * w` is sent to `g = (1 + c0) / c1`. -1 is not in Gt and
+    /// the identity is encoded as `g = 0`.
+    pub fn to_compressed(&self) -> [u8; 192] {
+        let [c0, c1] = self.0 .0;
+        let g = c1
+            .invert()
+            .map(|c1_inv| (Fq6::one() + c0) * c1_inv)
+            .unwrap_or(Fq6::zero());
+
+        let mut bytes = g.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Decode `to_compressed`, rejecting non-canonical coefficients and
+    /// elements outside the order r subgroup.
+    pub fn from_compressed(bytes: &[u8; 192]) -> CtOption<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+
+        Fq6::from_bytes_le(&bytes).and_then(|g| {
+            // (g + w) / (g - w) = ((g^2 + v) + 2g * w) / (g^2 - v)
+            let g2 = g.square();
+            let v = Fq6([Fq2::zero(), Fq2::one(), Fq2::zero()]);
+            let is_identity = g.ct_eq(&Fq6::zero());
+
+            (g2 - v).invert().and_then(|t| {
+                let f = Fq12([(g2 + v) * t, g.double() * t]);
+                let gt = Self(Fq12::conditional_select(&f, &Fq12::one(), is_identity));
+                let is_valid = gt.is_in_subgroup();
+                CtOption::new(gt, is_valid)
+            })
+        })
+    }
+}
+
+impl ConstantTimeEq for Gt {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
 }
 
 impl Add for Gt {
     type Output = Gt;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn add(self, rhs: Gt) -> Gt {
         Self(self.0 * rhs.0)
     }
@@ -30,3 +137,57 @@ impl Neg for Gt {
         Gt(self.0.conjugate())
     }
 }
+
+impl AddAssign for Gt {
+    fn add_assign(&mut self, rhs: Gt) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Gt {
+    type Output = Gt;
+
+    fn sub(self, rhs: Gt) -> Gt {
+        self + -rhs
+    }
+}
+
+impl SubAssign for Gt {
+    fn sub_assign(&mut self, rhs: Gt) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<Fr> for Gt {
+    type Output = Gt;
+
+    fn mul(self, rhs: Fr) -> Gt {
+        // f^k = f^k1 * (f^(q^2))^k2 with k1, k2 below 2^128, so a joint
+        // square and multiply over the table [1, f1, f2, f1 * f2] halves the
+        // squarings. The table lookup scans every entry, and negative halves
+        // become conjugates, which are inverses in the cyclotomic subgroup.
+        let [(neg1, k1), (neg2, k2)] = glv::decompose(&rhs);
+        let f1 = Fq12::conditional_select(&self.0, &self.0.conjugate(), neg1);
+        let f2 = self.0.frobenius_maps(2);
+        let f2 = Fq12::conditional_select(&f2, &f2.conjugate(), neg2);
+        let table = [Fq12::one(), f1, f2, f1 * f2];
+
+        let mut res = Fq12::one();
+        for i in (0..128).rev() {
+            res = res.cyclotomic_square();
+            let index = ((k1 >> i) & 1 | ((k2 >> i) & 1) << 1) as u8;
+            let mut t = table[0];
+            for (j, entry) in table.iter().enumerate() {
+                t = Fq12::conditional_select(&t, entry, index.ct_eq(&(j as u8)));
+            }
+            res *= t;
+        }
+        Gt(res)
+    }
+}
+
+impl MulAssign<Fr> for Gt {
+    fn mul_assign(&mut self, rhs: Fr) {
+        *self = *self * rhs;
+    }
+}
@@ -0,0 +1,77 @@
+//! HMAC (RFC 2104) and HKDF (RFC 5869) over the `sha2` hashes, for the key
+//! derivations of `SecretKey`. Keyed pads and derived blocks are wiped once
+//! they are used.
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::digest::{Digest, Output};
+use zeroize::Zeroize;
+
+#[derive(Clone)]
+pub(crate) struct Hmac<D> {
+    inner: D,
+    outer: D,
+}
+
+impl<D: Digest + BlockSizeUser + Clone> Hmac<D> {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        let mut pad = sha2::digest::generic_array::GenericArray::<u8, D::BlockSize>::default();
+        if key.len() > pad.len() {
+            let mut hashed = D::digest(key);
+            pad[..hashed.len()].copy_from_slice(&hashed);
+            hashed.as_mut_slice().zeroize();
+        } else {
+            pad[..key.len()].copy_from_slice(key);
+        }
+
+        pad.iter_mut().for_each(|b| *b ^= 0x36);
+        let inner = D::new_with_prefix(&pad);
+        pad.iter_mut().for_each(|b| *b ^= 0x36 ^ 0x5c);
+        let outer = D::new_with_prefix(&pad);
+        pad.as_mut_slice().zeroize();
+        Self { inner, outer }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub(crate) fn finalize(self) -> Output<D> {
+        let Self { inner, mut outer } = self;
+        let mut inner = inner.finalize();
+        outer.update(&inner);
+        inner.as_mut_slice().zeroize();
+        outer.finalize()
+    }
+}
+
+/// HKDF-Extract over the concatenation of `ikm`.
+pub(crate) fn hkdf_extract<D: Digest + BlockSizeUser + Clone>(
+    salt: &[u8],
+    ikm: &[&[u8]],
+) -> Output<D> {
+    let mut hmac = Hmac::<D>::new(salt);
+    ikm.iter().for_each(|part| hmac.update(part));
+    hmac.finalize()
+}
+
+/// HKDF-Expand, handing the blocks `T(1), T(2), ...` to `f` in turn instead
+/// of concatenating them, for at most the 255 blocks HKDF allows.
+pub(crate) fn hkdf_expand<D: Digest + BlockSizeUser + Clone>(
+    prk: &[u8],
+    info: &[u8],
+    blocks: u8,
+    mut f: impl FnMut(&[u8]),
+) {
+    let hmac = Hmac::<D>::new(prk);
+    let mut block = Output::<D>::default();
+    for i in 1..=blocks {
+        let mut next = hmac.clone();
+        if i > 1 {
+            next.update(&block);
+        }
+        next.update(info);
+        next.update(&[i]);
+        block = next.finalize();
+        f(&block);
+    }
+    block.as_mut_slice().zeroize();
+}
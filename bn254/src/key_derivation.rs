@@ -0,0 +1,140 @@
+//! Hierarchical deterministic keys after EIP-2333: the HKDF and Lamport tree
+//! of Ethereum validator keys, with every scalar reduced modulo the BN254
+//! group order instead of the BLS12-381 one, and paths written as in
+//! EIP-2334, `m/12381/60/0/0`. The tree has the same shape but different
+//! keys than the BLS12-381 one, so keys derived here never coincide with a
+//! validator key from the same seed.
+use core::fmt;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::fr::Fr;
+use crate::kdf::{hkdf_expand, hkdf_extract};
+use crate::limbs::from_le_bytes;
+use crate::secret_key::SecretKey;
+
+/// Errors deriving a key from a seed or along a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DerivationError {
+    /// The seed is shorter than the 32 bytes EIP-2333 asks for.
+    SeedTooShort,
+    /// The path is not `m` followed by `/`-separated decimal u32 indices.
+    InvalidPath,
+}
+
+impl fmt::Display for DerivationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SeedTooShort => write!(f, "seed is shorter than 32 bytes"),
+            Self::InvalidPath => write!(f, "invalid derivation path"),
+        }
+    }
+}
+
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+
+/// HKDF output per scalar, ceil(3 * ceil(log2 r) / 16) bytes, long enough
+/// that reducing it mod r has negligible bias. The BN254 r is one bit
+/// shorter than the BLS12-381 one, which leaves it at 48.
+const OKM_LEN: usize = 48;
+
+/// Chunks of a Lamport secret key, one per HKDF block.
+const LAMPORT_CHUNKS: u8 = 255;
+
+/// `HKDF_mod_r` of EIP-2333 with an empty `key_info`, rehashing the salt
+/// until the scalar is non-zero.
+fn hkdf_mod_r(ikm: &[u8]) -> SecretKey {
+    let mut salt: [u8; 32] = Sha256::digest(KEYGEN_SALT).into();
+    loop {
+        let mut prk = hkdf_extract::<Sha256>(&salt, &[ikm, &[0]]);
+        let mut okm = [0; 64];
+        let mut len = 0;
+        hkdf_expand::<Sha256>(&prk, &(OKM_LEN as u16).to_be_bytes(), 2, |block| {
+            okm[len..len + block.len()].copy_from_slice(block);
+            len += block.len();
+        });
+        prk.as_mut_slice().zeroize();
+
+        // the first OKM_LEN bytes as a big-endian integer, reversed into
+        // the 512-bit little-endian input of the reduction
+        let mut wide = [0; 64];
+        for (dst, src) in wide.iter_mut().zip(okm[..OKM_LEN].iter().rev()) {
+            *dst = *src;
+        }
+        let mut lo = from_le_bytes(wide[..32].try_into().unwrap());
+        let mut hi = from_le_bytes(wide[32..].try_into().unwrap());
+        let mut k = Fr::from_u512(lo, hi);
+        let key = SecretKey::try_from(k);
+        okm.zeroize();
+        wide.zeroize();
+        lo.zeroize();
+        hi.zeroize();
+        k.zeroize();
+
+        if let Ok(key) = key {
+            return key;
+        }
+        salt = Sha256::digest(salt).into();
+    }
+}
+
+/// `parent_SK_to_lamport_PK` of EIP-2333: the SHA-256 of the hashes of the
+/// Lamport chunks expanded from the parent key and from its complement,
+/// hashed as the chunks come out of HKDF rather than collected.
+fn compressed_lamport_pk(parent: &SecretKey, index: u32) -> [u8; 32] {
+    let salt = index.to_be_bytes();
+    let mut ikm = parent.to_bytes();
+    let mut pk = Sha256::new();
+    for _ in 0..2 {
+        let mut prk = hkdf_extract::<Sha256>(&salt, &[&ikm[..]]);
+        hkdf_expand::<Sha256>(&prk, &[], LAMPORT_CHUNKS, |chunk| {
+            pk.update(Sha256::digest(chunk));
+        });
+        prk.as_mut_slice().zeroize();
+        ikm.iter_mut().for_each(|b| *b = !*b);
+    }
+    pk.finalize().into()
+}
+
+/// The indices of an EIP-2334 path, checked in full before any is used.
+/// Indices are canonical decimals, so each path has a single spelling.
+fn path_indices(path: &str) -> Result<impl Iterator<Item = u32> + '_, DerivationError> {
+    let rest = path.strip_prefix('m').ok_or(DerivationError::InvalidPath)?;
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return Err(DerivationError::InvalidPath);
+    }
+    let components = rest.split('/').skip(1);
+    for component in components.clone() {
+        let is_canonical = !component.is_empty()
+            && component.bytes().all(|b| b.is_ascii_digit())
+            && (component == "0" || !component.starts_with('0'));
+        if !is_canonical || component.parse::<u32>().is_err() {
+            return Err(DerivationError::InvalidPath);
+        }
+    }
+    Ok(components.map(|component| component.parse().unwrap()))
+}
+
+impl SecretKey {
+    /// `derive_master_SK` of EIP-2333 from a seed of at least 32 bytes, such
+    /// as the 64-byte seed of a BIP-39 mnemonic.
+    pub fn derive_master(seed: &[u8]) -> Result<Self, DerivationError> {
+        if seed.len() < 32 {
+            return Err(DerivationError::SeedTooShort);
+        }
+        Ok(hkdf_mod_r(seed))
+    }
+
+    /// `derive_child_SK` of EIP-2333.
+    pub fn derive_child(&self, index: u32) -> Self {
+        hkdf_mod_r(&compressed_lamport_pk(self, index))
+    }
+
+    /// The key at `path` below the master key of `seed`, where `m` is the
+    /// master key itself and `m/12381/60/0/0` four child derivations away.
+    pub fn derive_path(seed: &[u8], path: &str) -> Result<Self, DerivationError> {
+        let indices = path_indices(path)?;
+        let master = Self::derive_master(seed)?;
+        Ok(indices.fold(master, |key, index| key.derive_child(index)))
+    }
+}
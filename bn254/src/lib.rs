@@ -1,3 +1,14 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "ark")]
+mod ark;
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+mod asm;
+mod curve;
+mod fixed_base;
 mod fq;
 mod fq12;
 mod fq2;
@@ -5,15 +16,42 @@ mod fq6;
 mod fr;
 mod g1;
 mod g2;
+mod glv;
 mod gt;
+mod kdf;
+mod key_derivation;
 mod limbs;
+#[cfg(any(target_arch = "wasm32", feature = "limbs29"))]
+mod limbs29;
 mod math;
+#[cfg(feature = "bip39")]
+mod mnemonic;
 mod pairing;
 mod params;
+mod safegcd;
+mod secret_key;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "bip39")]
+mod wordlist;
+#[cfg(feature = "zkcrypto")]
+mod zkcrypto;
 
+#[cfg(feature = "ark")]
+pub use ark::ArkError;
+pub use fixed_base::FixedBaseTable;
+pub use fq::Fq;
 pub use fq12::Fq12;
+pub use fq2::Fq2;
+pub use fq6::Fq6;
 pub use fr::Fr;
-pub use g1::G1Affine;
-pub use g2::{G2Affine, G2PairingAffine};
+pub use g1::{G1Affine, G1Projective};
+pub use g2::{G2Affine, G2PairingAffine, G2Projective};
 pub use gt::Gt;
-pub use pairing::AteParing;
+pub use key_derivation::DerivationError;
+#[cfg(feature = "bip39")]
+pub use mnemonic::{Mnemonic, MnemonicError};
+pub use pairing::{AteParing, PairingError};
+pub use secret_key::{SecretKey, SecretKeyError};
+#[cfg(feature = "zkcrypto")]
+pub use zkcrypto::{Bn254, G1Uncompressed, G2Uncompressed, MillerLoopOutput};
@@ -1,4 +1,5 @@
 use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 use crate::math::{adb, adc, mac, sba, sbb};
 
@@ -58,7 +59,13 @@ pub(crate) const fn double(a: [u64; 4], p: [u64; 4]) -> [u64; 4] {
 }
 
 #[inline(always)]
-pub(crate) const fn mul(a: [u64; 4], b: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+pub(crate) const fn const_mul(a: [u64; 4], b: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    mont(const_mul_wide(a, b), p, inv)
+}
+
+/// The 512-bit product `a * b`, without reduction.
+#[inline(always)]
+pub(crate) const fn const_mul_wide(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
     let s = a[0] as u128 * b[0] as u128;
     let (l0, c) = (s as u64, (s >> 64) as u64);
     let s = a[0] as u128 * b[1] as u128 + c as u128;
@@ -86,11 +93,19 @@ pub(crate) const fn mul(a: [u64; 4], b: [u64; 4], p: [u64; 4], inv: u64) -> [u64
     let (l5, c) = mac(l5, a[3], b[2], c);
     let (l6, l7) = mac(l6, a[3], b[3], c);
 
-    mont([l0, l1, l2, l3, l4, l5, l6, l7], p, inv)
+    [l0, l1, l2, l3, l4, l5, l6, l7]
+}
+
+#[cfg_attr(any(target_arch = "wasm32", feature = "limbs29"), allow(dead_code))]
+#[inline(always)]
+pub(crate) const fn const_square(a: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    mont(const_square_wide(a), p, inv)
 }
 
+/// The 512-bit square `a^2`, without reduction.
+#[cfg_attr(any(target_arch = "wasm32", feature = "limbs29"), allow(dead_code))]
 #[inline(always)]
-pub(crate) const fn square(a: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+pub(crate) const fn const_square_wide(a: [u64; 4]) -> [u64; 8] {
     let s = a[1] as u128 * a[0] as u128;
     let (l1, c) = (s as u64, (s >> 64) as u64);
     let s = a[2] as u128 * a[0] as u128 + c as u128;
@@ -124,21 +139,122 @@ pub(crate) const fn square(a: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
     let (l6, c) = mac(l6, a[3], a[3], c);
     let l7 = l7.wrapping_add(c);
 
-    mont([l0, l1, l2, l3, l4, l5, l6, l7], p, inv)
+    [l0, l1, l2, l3, l4, l5, l6, l7]
 }
 
+/// `a + b mod p * 2^256` for `a, b < p * 2^256`. Only the high half of the
+/// modulus is non-zero, so the correction only touches the high limbs.
 #[inline(always)]
-pub(crate) const fn neg(a: [u64; 4], p: [u64; 4]) -> [u64; 4] {
-    if (a[0] | a[1] | a[2] | a[3]) == 0 {
-        a
-    } else {
-        let (l0, b) = sba(p[0], a[0]);
-        let (l1, b) = sbb(p[1], a[1], b);
-        let (l2, b) = sbb(p[2], a[2], b);
-        let l3 = (p[3]).wrapping_sub(a[3]).wrapping_sub(b >> 63);
-
-        [l0, l1, l2, l3]
+pub(crate) const fn add_wide(a: [u64; 8], b: [u64; 8], p: [u64; 4]) -> [u64; 8] {
+    let (l0, c) = adb(a[0], b[0]);
+    let (l1, c) = adc(a[1], b[1], c);
+    let (l2, c) = adc(a[2], b[2], c);
+    let (l3, c) = adc(a[3], b[3], c);
+    let (l4, c) = adc(a[4], b[4], c);
+    let (l5, c) = adc(a[5], b[5], c);
+    let (l6, c) = adc(a[6], b[6], c);
+    let l7 = a[7].wrapping_add(b[7]).wrapping_add(c);
+
+    let [l4, l5, l6, l7] = sub([l4, l5, l6, l7], p, p);
+    [l0, l1, l2, l3, l4, l5, l6, l7]
+}
+
+/// `a - b mod p * 2^256` for `a, b < p * 2^256`.
+#[inline(always)]
+pub(crate) const fn sub_wide(a: [u64; 8], b: [u64; 8], p: [u64; 4]) -> [u64; 8] {
+    let (l0, brw) = sba(a[0], b[0]);
+    let (l1, brw) = sbb(a[1], b[1], brw);
+    let (l2, brw) = sbb(a[2], b[2], brw);
+    let (l3, brw) = sbb(a[3], b[3], brw);
+    let (l4, brw) = sbb(a[4], b[4], brw);
+    let (l5, brw) = sbb(a[5], b[5], brw);
+    let (l6, brw) = sbb(a[6], b[6], brw);
+    let (l7, brw) = sbb(a[7], b[7], brw);
+
+    let (l4, c) = adb(l4, p[0] & brw);
+    let (l5, c) = adc(l5, p[1] & brw, c);
+    let (l6, c) = adc(l6, p[2] & brw, c);
+    let l7 = l7.wrapping_add(p[3] & brw).wrapping_add(c);
+
+    [l0, l1, l2, l3, l4, l5, l6, l7]
+}
+
+/// Montgomery multiplication on the fastest backend for the target: the
+/// ADX one when the `asm` feature is enabled and the CPU supports it, the
+/// 29-bit limbs on `wasm32` or with the `limbs29` feature, and `const_mul`
+/// otherwise.
+#[inline(always)]
+pub(crate) fn mul(a: [u64; 4], b: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    if crate::asm::has_adx() {
+        // SAFETY: the required target features were detected
+        return unsafe { crate::asm::mul_adx(a, b, p, inv) };
+    }
+    #[cfg(any(target_arch = "wasm32", feature = "limbs29"))]
+    let res = crate::limbs29::mul(a, b, p, inv);
+    #[cfg(not(any(target_arch = "wasm32", feature = "limbs29")))]
+    let res = const_mul(a, b, p, inv);
+    res
+}
+
+/// Montgomery squaring, dispatched like `mul`.
+#[inline(always)]
+pub(crate) fn square(a: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    if crate::asm::has_adx() {
+        // SAFETY: the required target features were detected
+        return unsafe { crate::asm::square_adx(a, p, inv) };
     }
+    #[cfg(any(target_arch = "wasm32", feature = "limbs29"))]
+    let res = crate::limbs29::square(a, p, inv);
+    #[cfg(not(any(target_arch = "wasm32", feature = "limbs29")))]
+    let res = const_square(a, p, inv);
+    res
+}
+
+/// The 512-bit product `a * b`, dispatched like `mul`.
+#[inline(always)]
+pub(crate) fn mul_wide(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
+    #[cfg(any(target_arch = "wasm32", feature = "limbs29"))]
+    let res = crate::limbs29::mul_wide(a, b);
+    #[cfg(not(any(target_arch = "wasm32", feature = "limbs29")))]
+    let res = const_mul_wide(a, b);
+    res
+}
+
+/// Two independent `mul_wide`, side by side in the `simd128` lanes on the
+/// 29-bit limbs.
+#[inline(always)]
+pub(crate) fn mul_wide_x2(a: [[u64; 4]; 2], b: [[u64; 4]; 2]) -> [[u64; 8]; 2] {
+    #[cfg(any(target_arch = "wasm32", feature = "limbs29"))]
+    let res = crate::limbs29::mul_wide_x2(a, b);
+    #[cfg(not(any(target_arch = "wasm32", feature = "limbs29")))]
+    let res = [mul_wide(a[0], b[0]), mul_wide(a[1], b[1])];
+    res
+}
+
+/// Two independent Montgomery reductions of 512-bit values, paired like
+/// `mul_wide_x2`.
+#[inline(always)]
+pub(crate) fn reduce_wide_x2(t: [[u64; 8]; 2], p: [u64; 4], inv: u64) -> [[u64; 4]; 2] {
+    #[cfg(any(target_arch = "wasm32", feature = "limbs29"))]
+    let res = crate::limbs29::mont_x2(t, p, inv);
+    #[cfg(not(any(target_arch = "wasm32", feature = "limbs29")))]
+    let res = [mont(t[0], p, inv), mont(t[1], p, inv)];
+    res
+}
+
+#[inline(always)]
+pub(crate) const fn neg(a: [u64; 4], p: [u64; 4]) -> [u64; 4] {
+    let (l0, b) = sba(p[0], a[0]);
+    let (l1, b) = sbb(p[1], a[1], b);
+    let (l2, b) = sbb(p[2], a[2], b);
+    let l3 = (p[3]).wrapping_sub(a[3]).wrapping_sub(b >> 63);
+
+    // zero maps to zero rather than p, without branching on the value
+    let mask = (((a[0] | a[1] | a[2] | a[3]) == 0) as u64).wrapping_sub(1);
+
+    [l0 & mask, l1 & mask, l2 & mask, l3 & mask]
 }
 
 #[inline(always)]
@@ -189,27 +305,31 @@ pub(crate) const fn mont(a: [u64; 8], p: [u64; 4], inv: u64) -> [u64; 4] {
 }
 
 #[inline(always)]
-pub fn invert(
-    a: [u64; 4],
-    little_fermat: [u64; 4],
-    identity: [u64; 4],
-    p: [u64; 4],
-    inv: u64,
-) -> Option<[u64; 4]> {
-    let zero: [u64; 4] = [0; 4];
-    if a == zero {
-        None
-    } else {
-        Some(pow(a, little_fermat, identity, p, inv))
-    }
+pub(crate) fn ct_eq(a: [u64; 4], b: [u64; 4]) -> Choice {
+    a[0].ct_eq(&b[0]) & a[1].ct_eq(&b[1]) & a[2].ct_eq(&b[2]) & a[3].ct_eq(&b[3])
+}
+
+#[inline(always)]
+pub(crate) fn ct_is_zero(a: [u64; 4]) -> Choice {
+    (a[0] | a[1] | a[2] | a[3]).ct_eq(&0)
+}
+
+#[inline(always)]
+pub(crate) fn conditional_select(a: [u64; 4], b: [u64; 4], choice: Choice) -> [u64; 4] {
+    [
+        u64::conditional_select(&a[0], &b[0], choice),
+        u64::conditional_select(&a[1], &b[1], choice),
+        u64::conditional_select(&a[2], &b[2], choice),
+        u64::conditional_select(&a[3], &b[3], choice),
+    ]
 }
 
-pub type Bits = Vec<u8>;
+pub type Bits = [u8; 256];
 
 #[inline(always)]
 pub fn to_bits(val: [u64; 4]) -> Bits {
     let mut index = 256;
-    let mut bits: [u8; 256] = [0; 256];
+    let mut bits: Bits = [0; 256];
     for limb in val {
         for byte in limb.to_le_bytes().iter() {
             for i in 0..8 {
@@ -218,9 +338,11 @@ pub fn to_bits(val: [u64; 4]) -> Bits {
             }
         }
     }
-    bits.to_vec()
+    bits
 }
 
+/// Square and multiply-always, the exponent bits only drive a constant
+/// time select so the running time does not depend on either operand.
 #[inline(always)]
 pub(crate) fn pow(
     a: [u64; 4],
@@ -229,31 +351,19 @@ pub(crate) fn pow(
     p: [u64; 4],
     inv: u64,
 ) -> [u64; 4] {
-    let zero: [u64; 4] = [0; 4];
-    if b == zero {
-        return identity;
-    } else if a == zero {
-        return zero;
-    }
     let bits = to_bits(b);
     for &bit in bits.iter() {
         identity = square(identity, p, inv);
-        if bit == 1 {
-            identity = mul(identity, a, p, inv);
-        }
+        let product = mul(identity, a, p, inv);
+        identity = conditional_select(identity, product, Choice::from(bit));
     }
     identity
 }
 
-#[inline(always)]
-pub(crate) const fn little_fermat(p: [u64; 4]) -> [u64; 4] {
-    sub([0; 4], [2, 0, 0, 0], p)
-}
-
 #[inline(always)]
 const fn from_u512(limbs: [u64; 8], r2: [u64; 4], r3: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
-    let a = mul([limbs[0], limbs[1], limbs[2], limbs[3]], r2, p, inv);
-    let b = mul([limbs[4], limbs[5], limbs[6], limbs[7]], r3, p, inv);
+    let a = const_mul([limbs[0], limbs[1], limbs[2], limbs[3]], r2, p, inv);
+    let b = const_mul([limbs[4], limbs[5], limbs[6], limbs[7]], r3, p, inv);
     add(a, b, p)
 }
 
@@ -282,3 +392,90 @@ pub fn random_limbs(
         inv,
     )
 }
+
+#[inline(always)]
+pub(crate) fn from_le_bytes(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+#[inline(always)]
+pub(crate) fn to_le_bytes(limbs: [u64; 4]) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+/// Constant time `a < b`, the borrow out of `a - b`
+#[inline(always)]
+pub(crate) fn ct_lt(a: [u64; 4], b: [u64; 4]) -> Choice {
+    let (_, brw) = sba(a[0], b[0]);
+    let (_, brw) = sbb(a[1], b[1], brw);
+    let (_, brw) = sbb(a[2], b[2], brw);
+    let (_, brw) = sbb(a[3], b[3], brw);
+    Choice::from((brw >> 63) as u8)
+}
+
+/// Parse an unsigned integer in the given radix, failing on an empty
+/// string, an invalid digit or a value not below `p`.
+pub(crate) fn from_str_radix(s: &str, radix: u32, p: [u64; 4]) -> Option<[u64; 4]> {
+    if s.is_empty() || !(2..=36).contains(&radix) {
+        return None;
+    }
+    let mut acc = [0u64; 4];
+    for c in s.chars() {
+        let digit = c.to_digit(radix)? as u64;
+        // acc = acc * radix + digit, rejecting anything past 256 bits
+        let (l0, c) = mac(digit, acc[0], radix as u64, 0);
+        let (l1, c) = mac(0, acc[1], radix as u64, c);
+        let (l2, c) = mac(0, acc[2], radix as u64, c);
+        let (l3, c) = mac(0, acc[3], radix as u64, c);
+        if c != 0 {
+            return None;
+        }
+        acc = [l0, l1, l2, l3];
+    }
+    bool::from(ct_lt(acc, p)).then_some(acc)
+}
+
+/// Inputs shared by the tests of the other backends against this one.
+#[cfg(test)]
+pub(crate) mod tests {
+    use rand_core::{OsRng, RngCore};
+
+    /// Uniform limbs below `p` by rejection sampling.
+    pub(crate) fn random_below(p: [u64; 4]) -> [u64; 4] {
+        loop {
+            let mut a = [0; 4];
+            a.iter_mut().for_each(|l| *l = OsRng.next_u64());
+            a[3] &= u64::MAX >> p[3].leading_zeros();
+            if a.iter().rev().lt(p.iter().rev()) {
+                return a;
+            }
+        }
+    }
+
+    /// Values at the edges of `[0, p)`, where dropped carries would show.
+    pub(crate) fn edge_cases(p: [u64; 4]) -> [[u64; 4]; 7] {
+        let minus = |k: u64| [p[0] - k, p[1], p[2], p[3]];
+        [
+            [0; 4],
+            [1, 0, 0, 0],
+            [u64::MAX, 0, 0, 0],
+            [u64::MAX, u64::MAX, u64::MAX, 0],
+            minus(1),
+            minus(2),
+            [
+                p[0] >> 1 | p[1] << 63,
+                p[1] >> 1 | p[2] << 63,
+                p[2] >> 1 | p[3] << 63,
+                p[3] >> 1,
+            ],
+        ]
+    }
+}
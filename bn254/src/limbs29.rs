@@ -0,0 +1,449 @@
+//! Montgomery arithmetic on nine 29-bit limbs, for targets without a native
+//! 64x64 -> 128 bit multiplication such as `wasm32`.
+//!
+//! Elements keep the usual `[u64; 4]` Montgomery form with `R = 2^256`, the
+//! limbs are only split for the multiplication. A product of two 29-bit
+//! limbs fits in 58 bits, so whole columns of the schoolbook product and of
+//! the reduction accumulate in a `u64` without carry propagation, and the
+//! carries are settled once per column. The eight reduction rounds of 29
+//! bits are followed by one of 24 bits, which divides by exactly 2^256.
+//!
+//! The `_x2` functions compute two independent products or reductions at
+//! once. With the `simd128` feature on a `wasm32` build with the `simd128`
+//! target feature, they run side by side in the two 64-bit lanes.
+use crate::limbs::sub;
+
+const MASK: u64 = (1 << 29) - 1;
+
+/// Nine 29-bit limbs of a 256-bit integer, the top one holding 24 bits.
+#[inline(always)]
+const fn split(a: [u64; 4]) -> [u64; 9] {
+    [
+        a[0] & MASK,
+        (a[0] >> 29) & MASK,
+        (a[0] >> 58 | a[1] << 6) & MASK,
+        (a[1] >> 23) & MASK,
+        (a[1] >> 52 | a[2] << 12) & MASK,
+        (a[2] >> 17) & MASK,
+        (a[2] >> 46 | a[3] << 18) & MASK,
+        (a[3] >> 11) & MASK,
+        a[3] >> 40,
+    ]
+}
+
+/// Eighteen 29-bit limbs of a 512-bit integer, the top one holding 19 bits.
+#[inline(always)]
+const fn split_wide(a: [u64; 8]) -> [u64; 18] {
+    let lo = split([a[0], a[1], a[2], a[3]]);
+    // the limbs above the ninth start at bit 261 = 256 + 5
+    let hi = split([
+        a[4] >> 5 | a[5] << 59,
+        a[5] >> 5 | a[6] << 59,
+        a[6] >> 5 | a[7] << 59,
+        a[7] >> 5,
+    ]);
+    [
+        lo[0],
+        lo[1],
+        lo[2],
+        lo[3],
+        lo[4],
+        lo[5],
+        lo[6],
+        lo[7],
+        (a[3] >> 40 | a[4] << 24) & MASK,
+        hi[0],
+        hi[1],
+        hi[2],
+        hi[3],
+        hi[4],
+        hi[5],
+        hi[6],
+        hi[7],
+        hi[8],
+    ]
+}
+
+/// Settle the carries of `N` columns into 29-bit limbs, the last limb
+/// keeping whatever is left.
+#[inline(always)]
+fn normalize<const N: usize>(mut t: [u64; N]) -> [u64; N] {
+    for i in 0..N - 1 {
+        t[i + 1] += t[i] >> 29;
+        t[i] &= MASK;
+    }
+    t
+}
+
+/// Join ten 29-bit limbs into 290 bits.
+#[inline(always)]
+const fn join(c: [u64; 10]) -> [u64; 5] {
+    [
+        c[0] | c[1] << 29 | c[2] << 58,
+        c[2] >> 6 | c[3] << 23 | c[4] << 52,
+        c[4] >> 12 | c[5] << 17 | c[6] << 46,
+        c[6] >> 18 | c[7] << 11 | c[8] << 40,
+        c[8] >> 24 | c[9] << 5,
+    ]
+}
+
+/// The columns of the schoolbook product, each below `9 * 2^58`.
+#[inline(always)]
+fn product(a: [u64; 9], b: [u64; 9]) -> [u64; 18] {
+    let mut t = [0; 18];
+    for i in 0..9 {
+        for j in 0..9 {
+            t[i + j] += a[i] * b[j];
+        }
+    }
+    t
+}
+
+/// The columns of the square, each below `9 * 2^58`.
+#[inline(always)]
+fn square_product(a: [u64; 9]) -> [u64; 18] {
+    let mut t = [0; 18];
+    for i in 0..9 {
+        for j in i + 1..9 {
+            t[i + j] += a[i] * a[j];
+        }
+    }
+    for (i, &a) in a.iter().enumerate() {
+        t[2 * i] = (t[2 * i] << 1) + a * a;
+        t[2 * i + 1] <<= 1;
+    }
+    t
+}
+
+/// `t * 2^-256 mod p` for columns `t` below `2^62` whose value is below
+/// `p * 2^256`.
+#[inline(always)]
+fn reduce(mut t: [u64; 18], p: [u64; 4], inv: u64) -> [u64; 4] {
+    let q = split(p);
+    for i in 0..8 {
+        let m = t[i].wrapping_mul(inv) & MASK;
+        for j in 0..9 {
+            t[i + j] += m * q[j];
+        }
+        t[i + 1] += t[i] >> 29;
+    }
+    let m = t[8].wrapping_mul(inv) & ((1 << 24) - 1);
+    for j in 0..9 {
+        t[8 + j] += m * q[j];
+    }
+
+    let mut c = [0; 10];
+    c.copy_from_slice(&t[8..]);
+    let w = join(normalize(c));
+    let r = [
+        w[0] >> 24 | w[1] << 40,
+        w[1] >> 24 | w[2] << 40,
+        w[2] >> 24 | w[3] << 40,
+        w[3] >> 24 | w[4] << 40,
+    ];
+    sub(r, p, p)
+}
+
+/// Montgomery multiplication `a * b * 2^-256 mod p`.
+#[inline(always)]
+pub(crate) fn mul(a: [u64; 4], b: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    reduce(product(split(a), split(b)), p, inv)
+}
+
+/// Montgomery squaring `a^2 * 2^-256 mod p`.
+#[inline(always)]
+pub(crate) fn square(a: [u64; 4], p: [u64; 4], inv: u64) -> [u64; 4] {
+    reduce(square_product(split(a)), p, inv)
+}
+
+/// The 512-bit product `a * b`, without reduction.
+#[inline(always)]
+pub(crate) fn mul_wide(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
+    let c = normalize(product(split(a), split(b)));
+    let lo = join([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7], c[8], 0]);
+    let hi = join([
+        c[9], c[10], c[11], c[12], c[13], c[14], c[15], c[16], c[17], 0,
+    ]);
+    // the high limbs start at bit 261 = 256 + 5
+    [
+        lo[0],
+        lo[1],
+        lo[2],
+        lo[3],
+        lo[4] | hi[0] << 5,
+        hi[0] >> 59 | hi[1] << 5,
+        hi[1] >> 59 | hi[2] << 5,
+        hi[2] >> 59 | hi[3] << 5,
+    ]
+}
+
+/// Two independent `mul_wide`.
+#[cfg(not(all(
+    target_arch = "wasm32",
+    target_feature = "simd128",
+    feature = "simd128"
+)))]
+#[inline(always)]
+pub(crate) fn mul_wide_x2(a: [[u64; 4]; 2], b: [[u64; 4]; 2]) -> [[u64; 8]; 2] {
+    [mul_wide(a[0], b[0]), mul_wide(a[1], b[1])]
+}
+
+/// Two independent Montgomery reductions `t * 2^-256 mod p` of values
+/// `t < p * 2^256`.
+#[cfg(not(all(
+    target_arch = "wasm32",
+    target_feature = "simd128",
+    feature = "simd128"
+)))]
+#[inline(always)]
+pub(crate) fn mont_x2(t: [[u64; 8]; 2], p: [u64; 4], inv: u64) -> [[u64; 4]; 2] {
+    t.map(|t| reduce(split_wide(t), p, inv))
+}
+
+#[cfg(all(
+    target_arch = "wasm32",
+    target_feature = "simd128",
+    feature = "simd128"
+))]
+pub(crate) use simd::{mont_x2, mul_wide_x2};
+
+/// The same algorithms with one operand per 64-bit lane. `extmul` takes its
+/// factors from the two low 32-bit lanes, so limbs are packed with a
+/// shuffle before every multiplication.
+#[cfg(all(
+    target_arch = "wasm32",
+    target_feature = "simd128",
+    feature = "simd128"
+))]
+mod simd {
+    use super::{join, split, split_wide, MASK};
+    use crate::limbs::sub;
+    use core::arch::wasm32::{
+        i32x4_shuffle, i64x2_add, u32x4_splat, u64x2, u64x2_extmul_low_u32x4, u64x2_extract_lane,
+        u64x2_shr, u64x2_splat, v128, v128_and,
+    };
+
+    #[inline(always)]
+    fn pack(v: v128) -> v128 {
+        i32x4_shuffle::<0, 2, 0, 2>(v, v)
+    }
+
+    #[inline(always)]
+    fn lanes<const N: usize>(a: [u64; N], b: [u64; N]) -> [v128; N] {
+        core::array::from_fn(|k| u64x2(a[k], b[k]))
+    }
+
+    #[inline(always)]
+    fn unlanes<const N: usize>(v: [v128; N]) -> [[u64; N]; 2] {
+        [
+            v.map(|l| u64x2_extract_lane::<0>(l)),
+            v.map(|l| u64x2_extract_lane::<1>(l)),
+        ]
+    }
+
+    #[inline(always)]
+    fn normalize<const N: usize>(mut t: [v128; N]) -> [v128; N] {
+        let mask = u64x2_splat(MASK);
+        for i in 0..N - 1 {
+            t[i + 1] = i64x2_add(t[i + 1], u64x2_shr(t[i], 29));
+            t[i] = v128_and(t[i], mask);
+        }
+        t
+    }
+
+    #[inline(always)]
+    fn product(a: [v128; 9], b: [v128; 9]) -> [v128; 18] {
+        let a = a.map(pack);
+        let b = b.map(pack);
+        let mut t = [u64x2_splat(0); 18];
+        for i in 0..9 {
+            for j in 0..9 {
+                t[i + j] = i64x2_add(t[i + j], u64x2_extmul_low_u32x4(a[i], b[j]));
+            }
+        }
+        t
+    }
+
+    #[inline(always)]
+    fn reduce(mut t: [v128; 18], p: [u64; 4], inv: u64) -> [[u64; 4]; 2] {
+        let q = split(p).map(|l| u32x4_splat(l as u32));
+        let inv = u32x4_splat(inv as u32);
+        let mask = u64x2_splat(MASK);
+        for i in 0..9 {
+            let mut m = v128_and(u64x2_extmul_low_u32x4(pack(t[i]), inv), mask);
+            if i == 8 {
+                m = v128_and(m, u64x2_splat((1 << 24) - 1));
+            }
+            let m = pack(m);
+            for j in 0..9 {
+                t[i + j] = i64x2_add(t[i + j], u64x2_extmul_low_u32x4(m, q[j]));
+            }
+            if i < 8 {
+                t[i + 1] = i64x2_add(t[i + 1], u64x2_shr(t[i], 29));
+            }
+        }
+
+        let mut c = [u64x2_splat(0); 10];
+        c.copy_from_slice(&t[8..]);
+        unlanes(normalize(c)).map(|c| {
+            let w = join(c);
+            let r = [
+                w[0] >> 24 | w[1] << 40,
+                w[1] >> 24 | w[2] << 40,
+                w[2] >> 24 | w[3] << 40,
+                w[3] >> 24 | w[4] << 40,
+            ];
+            sub(r, p, p)
+        })
+    }
+
+    /// Two independent `mul_wide`.
+    #[inline(always)]
+    pub(crate) fn mul_wide_x2(a: [[u64; 4]; 2], b: [[u64; 4]; 2]) -> [[u64; 8]; 2] {
+        let a = lanes(split(a[0]), split(a[1]));
+        let b = lanes(split(b[0]), split(b[1]));
+        unlanes(normalize(product(a, b))).map(|c| {
+            let lo = join([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7], c[8], 0]);
+            let hi = join([
+                c[9], c[10], c[11], c[12], c[13], c[14], c[15], c[16], c[17], 0,
+            ]);
+            [
+                lo[0],
+                lo[1],
+                lo[2],
+                lo[3],
+                lo[4] | hi[0] << 5,
+                hi[0] >> 59 | hi[1] << 5,
+                hi[1] >> 59 | hi[2] << 5,
+                hi[2] >> 59 | hi[3] << 5,
+            ]
+        })
+    }
+
+    /// Two independent Montgomery reductions.
+    #[inline(always)]
+    pub(crate) fn mont_x2(t: [[u64; 8]; 2], p: [u64; 4], inv: u64) -> [[u64; 4]; 2] {
+        reduce(lanes(split_wide(t[0]), split_wide(t[1])), p, inv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limbs::tests::{edge_cases, random_below};
+    use crate::limbs::{const_mul, const_mul_wide, const_square};
+    use crate::{fq, fr, limbs};
+    use rand_core::{OsRng, RngCore};
+
+    extern crate std;
+    use core::hint::black_box;
+    use std::println;
+    use std::time::Instant;
+
+    const ITERATIONS: usize = 100_000;
+
+    /// Double-width values below `p * 2^256`, the inputs `mont_x2` accepts.
+    fn random_wide(p: [u64; 4]) -> [u64; 8] {
+        let hi = random_below(p);
+        let mut t = [0; 8];
+        t[..4].iter_mut().for_each(|l| *l = OsRng.next_u64());
+        t[4..].copy_from_slice(&hi);
+        t
+    }
+
+    fn agree(p: [u64; 4], inv: u64) {
+        let edges = edge_cases(p);
+        for a in edges {
+            for b in edges {
+                assert_eq!(
+                    mul(a, b, p, inv),
+                    const_mul(a, b, p, inv),
+                    "{a:x?} * {b:x?}"
+                );
+                assert_eq!(mul_wide(a, b), const_mul_wide(a, b), "{a:x?} * {b:x?}");
+            }
+            assert_eq!(square(a, p, inv), const_square(a, p, inv), "{a:x?}^2");
+        }
+        for _ in 0..ITERATIONS {
+            let (a, b) = (random_below(p), random_below(p));
+            let (c, d) = (random_below(p), random_below(p));
+            assert_eq!(
+                mul(a, b, p, inv),
+                const_mul(a, b, p, inv),
+                "{a:x?} * {b:x?}"
+            );
+            assert_eq!(square(a, p, inv), const_square(a, p, inv), "{a:x?}^2");
+            assert_eq!(
+                mul_wide_x2([a, c], [b, d]),
+                [const_mul_wide(a, b), const_mul_wide(c, d)],
+                "{a:x?} * {b:x?}, {c:x?} * {d:x?}"
+            );
+
+            let (t, u) = (random_wide(p), random_wide(p));
+            assert_eq!(
+                mont_x2([t, u], p, inv),
+                [limbs::mont(t, p, inv), limbs::mont(u, p, inv)],
+                "{t:x?}, {u:x?}"
+            );
+        }
+        let top = [
+            u64::MAX,
+            u64::MAX,
+            u64::MAX,
+            u64::MAX,
+            p[0] - 1,
+            p[1],
+            p[2],
+            p[3],
+        ];
+        assert_eq!(mont_x2([top; 2], p, inv), [limbs::mont(top, p, inv); 2]);
+    }
+
+    #[test]
+    fn fq_backends_agree() {
+        agree(fq::MODULUS, fq::INV);
+    }
+
+    #[test]
+    fn fr_backends_agree() {
+        agree(fr::MODULUS, fr::INV);
+    }
+
+    /// Nanoseconds per call of `f` on `N` inputs, the best of 100 runs.
+    fn time<const N: usize, T: Copy, R>(inputs: &[T; N], f: impl Fn(T) -> R) -> f64 {
+        (0..100)
+            .map(|_| {
+                let start = Instant::now();
+                for &x in inputs {
+                    black_box(f(black_box(x)));
+                }
+                start.elapsed().as_nanos() as f64 / N as f64
+            })
+            .fold(f64::MAX, f64::min)
+    }
+
+    /// What the conversions to and from 29-bit limbs cost next to the
+    /// multiplication itself, and next to the 64-bit limbs. Meaningful in
+    /// an optimized build only, and on `wasm32` in particular:
+    ///
+    /// cargo test --release --target wasm32-wasip1 --lib limbs29 -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn split_cost() {
+        let (p, inv) = (fq::MODULUS, fq::INV);
+        let inputs: [_; 1000] = core::array::from_fn(|_| (random_below(p), random_below(p)));
+        let columns = inputs.map(|(a, b)| {
+            let c = normalize(product(split(a), split(b)));
+            core::array::from_fn::<_, 10, _>(|i| c[8 + i])
+        });
+
+        let split_ns = time(&inputs, |(a, b)| (split(a), split(b)));
+        let join_ns = time(&columns, join);
+        let full = time(&inputs, |(a, b)| mul(a, b, p, inv));
+        let portable = time(&inputs, |(a, b)| const_mul(a, b, p, inv));
+        println!("split of both operands: {split_ns:.1} ns");
+        println!("join of the result: {join_ns:.1} ns");
+        println!("mul with the conversions: {full:.1} ns");
+        println!("64-bit const_mul: {portable:.1} ns");
+    }
+}
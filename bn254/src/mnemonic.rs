@@ -0,0 +1,239 @@
+//! BIP-39 mnemonics over the English wordlist, and the seeds behind them.
+//!
+//! A mnemonic of 12 to 24 words encodes 128 to 256 bits of entropy followed
+//! by the first bits of its SHA-256 as a checksum, 11 bits per word. The
+//! seed is PBKDF2-HMAC-SHA512 of the words joined by single spaces, salted
+//! with `"mnemonic"` and the NFKD normalized passphrase, and
+//! `Mnemonic::derive_key` feeds it to the EIP-2333 tree of `SecretKey`.
+use core::fmt;
+use rand_core::RngCore;
+use sha2::{Digest, Sha256, Sha512};
+use subtle::{Choice, ConstantTimeEq};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::kdf::Hmac;
+use crate::key_derivation::DerivationError;
+use crate::secret_key::SecretKey;
+use crate::wordlist::ENGLISH;
+
+/// Errors building a mnemonic from entropy or words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MnemonicError {
+    /// Not 12, 15, 18, 21 or 24 words, or entropy of other than 16, 20, 24,
+    /// 28 or 32 bytes.
+    InvalidLength,
+    /// A word is not in the English wordlist.
+    UnknownWord,
+    /// The checksum bits do not match the entropy.
+    InvalidChecksum,
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "invalid mnemonic length"),
+            Self::UnknownWord => write!(f, "unknown mnemonic word"),
+            Self::InvalidChecksum => write!(f, "invalid mnemonic checksum"),
+        }
+    }
+}
+
+const MAX_WORDS: usize = 24;
+
+/// Bits per word, indexing the 2048 words of the list.
+const WORD_BITS: usize = 11;
+
+/// Longest sentence, 24 words of at most 8 letters and the spaces between.
+const MAX_SENTENCE_LEN: usize = MAX_WORDS * 9 - 1;
+
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// The top bits of the checksum byte kept, one per 32 bits of entropy.
+fn checksum_mask(entropy_len: usize) -> u8 {
+    (0xff00u16 >> (entropy_len / 4)) as u8
+}
+
+/// A BIP-39 mnemonic. It holds secret entropy, so it is neither `Copy` nor
+/// `Clone`, its `Debug` output is redacted and it is wiped on drop. Its
+/// `Display` output is the phrase itself.
+pub struct Mnemonic {
+    /// Entropy, followed by the checksum byte and zero padding so every
+    /// word reads three whole bytes
+    bytes: [u8; 35],
+    entropy_len: usize,
+}
+
+impl Mnemonic {
+    /// A mnemonic of `words` words over fresh entropy, 12 words for 128 bits
+    /// of security and 24 for 256.
+    pub fn generate<R: RngCore>(rand: &mut R, words: usize) -> Result<Self, MnemonicError> {
+        let mut entropy = Zeroizing::new([0; 32]);
+        let len = words * 4 / 3;
+        if !words.is_multiple_of(3) || !(16..=32).contains(&len) {
+            return Err(MnemonicError::InvalidLength);
+        }
+        rand.fill_bytes(&mut entropy[..len]);
+        Self::from_entropy(&entropy[..len])
+    }
+
+    /// The mnemonic of 16, 20, 24, 28 or 32 bytes of entropy.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, MnemonicError> {
+        if !entropy.len().is_multiple_of(4) || !(16..=32).contains(&entropy.len()) {
+            return Err(MnemonicError::InvalidLength);
+        }
+        let mut bytes = [0; 35];
+        bytes[..entropy.len()].copy_from_slice(entropy);
+        // only the checksum bits, so parsing the words gives equal bytes
+        bytes[entropy.len()] = Sha256::digest(entropy)[0] & checksum_mask(entropy.len());
+        Ok(Self {
+            bytes,
+            entropy_len: entropy.len(),
+        })
+    }
+
+    /// Parse words separated by whitespace, checking each against the
+    /// wordlist and the checksum against the entropy.
+    pub fn parse(phrase: &str) -> Result<Self, MnemonicError> {
+        let mut bytes = Zeroizing::new([0; 35]);
+        let mut words = 0;
+        for word in phrase.split_whitespace() {
+            if words == MAX_WORDS {
+                return Err(MnemonicError::InvalidLength);
+            }
+            let index = ENGLISH
+                .binary_search(&word)
+                .map_err(|_| MnemonicError::UnknownWord)?;
+            for bit in 0..WORD_BITS {
+                let pos = words * WORD_BITS + bit;
+                let value = (index >> (WORD_BITS - 1 - bit)) as u8 & 1;
+                bytes[pos / 8] |= value << (7 - pos % 8);
+            }
+            words += 1;
+        }
+        if !words.is_multiple_of(3) || words < 12 {
+            return Err(MnemonicError::InvalidLength);
+        }
+
+        let entropy_len = words * 4 / 3;
+        let expected = Sha256::digest(&bytes[..entropy_len])[0];
+        let mnemonic = Self {
+            bytes: *bytes,
+            entropy_len,
+        };
+        if (mnemonic.bytes[entropy_len] ^ expected) & checksum_mask(entropy_len) != 0 {
+            return Err(MnemonicError::InvalidChecksum);
+        }
+        Ok(mnemonic)
+    }
+
+    pub fn entropy(&self) -> &[u8] {
+        &self.bytes[..self.entropy_len]
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.entropy_len * 3 / 4
+    }
+
+    /// The words of the phrase in order.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        (0..self.word_count()).map(|i| {
+            let pos = i * WORD_BITS;
+            let window = u32::from_be_bytes([
+                0,
+                self.bytes[pos / 8],
+                self.bytes[pos / 8 + 1],
+                self.bytes[pos / 8 + 2],
+            ]);
+            ENGLISH[(window >> (24 - WORD_BITS - pos % 8)) as usize & 0x7ff]
+        })
+    }
+
+    /// The 64-byte BIP-39 seed, wiped when the returned buffer is dropped.
+    pub fn to_seed(&self, passphrase: &str) -> Zeroizing<[u8; 64]> {
+        let mut sentence = [0; MAX_SENTENCE_LEN];
+        let mut len = 0;
+        for (i, word) in self.words().enumerate() {
+            if i > 0 {
+                sentence[len] = b' ';
+                len += 1;
+            }
+            sentence[len..len + word.len()].copy_from_slice(word.as_bytes());
+            len += word.len();
+        }
+        let prf = Hmac::<Sha512>::new(&sentence[..len]);
+        sentence.zeroize();
+
+        // PBKDF2 with a single block, as the seed is one SHA-512 output
+        let mut mac = prf.clone();
+        mac.update(b"mnemonic");
+        for c in passphrase.nfkd() {
+            mac.update(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        mac.update(&1u32.to_be_bytes());
+        let mut u = mac.finalize();
+        let mut seed = Zeroizing::new([0; 64]);
+        seed.copy_from_slice(&u);
+        for _ in 1..PBKDF2_ROUNDS {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            seed.iter_mut().zip(u.iter()).for_each(|(s, u)| *s ^= u);
+        }
+        u.as_mut_slice().zeroize();
+        seed
+    }
+
+    /// The key at an EIP-2334 `path` of the EIP-2333 tree over the seed,
+    /// such as `m/12381/60/0/0`.
+    pub fn derive_key(&self, passphrase: &str, path: &str) -> Result<SecretKey, DerivationError> {
+        SecretKey::derive_path(&*self.to_seed(passphrase), path)
+    }
+}
+
+impl Zeroize for Mnemonic {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+impl Drop for Mnemonic {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Mnemonic {}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.words().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(word)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mnemonic(<{} words redacted>)", self.word_count())
+    }
+}
+
+impl ConstantTimeEq for Mnemonic {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.ct_eq(&other.bytes)
+            & (self.entropy_len as u64).ct_eq(&(other.entropy_len as u64))
+    }
+}
+
+impl PartialEq for Mnemonic {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Mnemonic {}
@@ -1,10 +1,15 @@
+use core::fmt;
+
 use crate::fq::Fq;
 use crate::fq12::Fq12;
 use crate::fq2::Fq2;
 use crate::g1::G1Affine;
-use crate::g2::{G2Affine, G2PairingAffine};
+use crate::g2::{G2Affine, G2PairingAffine, PAIRING_COEFFS_LEN};
 use crate::gt::Gt;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 // 6U+2 for in NAF form
 pub const SIX_U_PLUS_2_NAF: [i8; 65] = [
     0, 0, 0, 1, 0, 1, 0, -1, 0, 0, 1, -1, 0, 0, 1, 0, 0, 1, 1, 0, -1, 0, 0, 1, 0, -1, 0, 0, 0, 0,
@@ -27,6 +32,24 @@ pub const XI_TO_Q_MINUS_1_OVER_2: Fq2 = Fq2([
     ]),
 ]);
 
+/// Errors returned by the fallible pairing entry points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairingError {
+    /// A G1 input does not satisfy the curve equation.
+    G1NotOnCurve,
+    /// A prepared G2 input is off the twist or outside the order r subgroup.
+    MalformedG2Prepared,
+}
+
+impl fmt::Display for PairingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::G1NotOnCurve => write!(f, "g1 point is not on the curve"),
+            Self::MalformedG2Prepared => write!(f, "malformed prepared g2 point"),
+        }
+    }
+}
+
 /// Ate pairing struct holds necessary components for pairing.
 /// `pairing` function takes G1 and G2 group elements and output
 /// GT target group element.
@@ -39,11 +62,48 @@ impl AteParing {
     }
 
     pub fn multi_miller_loop(pairs: &[(G1Affine, G2PairingAffine)]) -> Fq12 {
-        let mut pairs = pairs
-            .iter()
-            .filter(|(a, b)| !(a.is_identity()) && !b.is_identity())
-            .map(|(g1, g2)| (g1, g2.coeffs.iter()))
-            .collect::<Vec<_>>();
+        Self::miller_loop(pairs.iter().map(|(g1, g2)| (g1, g2)))
+    }
+
+    /// Same as `multi_miller_loop` but borrows the points, so callers can
+    /// pair existing prepared G2 points without copying their coefficients.
+    pub fn multi_miller_loop_refs(pairs: &[(&G1Affine, &G2PairingAffine)]) -> Fq12 {
+        Self::miller_loop(pairs.iter().copied())
+    }
+
+    /// Fallible `multi_miller_loop_refs` which rejects G1 points off the curve
+    /// and prepared G2 points off the twist or outside the order r subgroup,
+    /// instead of returning a meaningless value. The coefficients are only
+    /// built by `G2PairingAffine::from`, so the point is all there is to check.
+    pub fn try_multi_miller_loop(
+        pairs: &[(&G1Affine, &G2PairingAffine)],
+    ) -> Result<Fq12, PairingError> {
+        for (g1, g2) in pairs {
+            if !g1.is_on_curve() {
+                return Err(PairingError::G1NotOnCurve);
+            }
+            if !g2.is_well_formed() {
+                return Err(PairingError::MalformedG2Prepared);
+            }
+        }
+        Ok(Self::multi_miller_loop_refs(pairs))
+    }
+
+    fn miller_loop<'a, I>(pairs: I) -> Fq12
+    where
+        I: Iterator<Item = (&'a G1Affine, &'a G2PairingAffine)> + Clone,
+    {
+        // every prepared point consumes its coefficients in the same order,
+        // so a single shared index walks all of them in lockstep
+        let pairs = pairs.filter(|(a, b)| !(a.is_identity()) && !b.is_identity());
+        let mut index = 0;
+        let mut ell = |acc: Fq12| {
+            let acc = pairs
+                .clone()
+                .fold(acc, |acc, (g1, g2)| acc.untwist(g2.coeffs[index], *g1));
+            index += 1;
+            acc
+        };
 
         let mut acc = Fq12::one();
 
@@ -51,37 +111,76 @@ impl AteParing {
             if i != SIX_U_PLUS_2_NAF.len() - 1 {
                 acc.square_assign();
             }
-            for &mut (p, ref mut coeffs) in &mut pairs {
-                acc = acc.untwist(*coeffs.next().unwrap(), *p);
-            }
+            acc = ell(acc);
             let x = SIX_U_PLUS_2_NAF[i - 1];
             match x {
                 1 => {
-                    for &mut (p, ref mut coeffs) in &mut pairs {
-                        acc = acc.untwist(*coeffs.next().unwrap(), *p);
-                    }
+                    acc = ell(acc);
                 }
                 -1 => {
-                    for &mut (p, ref mut coeffs) in &mut pairs {
-                        acc = acc.untwist(*coeffs.next().unwrap(), *p);
-                    }
+                    acc = ell(acc);
                 }
                 _ => continue,
             }
         }
 
-        for &mut (p, ref mut coeffs) in &mut pairs {
-            acc = acc.untwist(*coeffs.next().unwrap(), *p);
-        }
+        acc = ell(acc);
+        acc = ell(acc);
 
-        for &mut (p, ref mut coeffs) in &mut pairs {
-            acc = acc.untwist(*coeffs.next().unwrap(), *p);
-        }
-
-        for &mut (_p, ref mut coeffs) in &mut pairs {
-            assert_eq!(coeffs.next(), None);
-        }
+        debug_assert_eq!(index, PAIRING_COEFFS_LEN);
 
         acc
     }
+
+    /// Product of pairings `e(g1_0, g2_0) + ... + e(g1_n, g2_n)` sharing a
+    /// single final exponentiation. Prepared points are kept on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn multi_pairing(pairs: &[(G1Affine, G2Affine)]) -> Gt {
+        let pairs = pairs
+            .iter()
+            .map(|&(g1, g2)| (g1, G2PairingAffine::from(g2)))
+            .collect::<Vec<_>>();
+        Self::multi_miller_loop(&pairs).final_exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AteParing, PairingError};
+    use crate::fq::Fq;
+    use crate::fq2::Fq2;
+    use crate::g1::G1Affine;
+    use crate::g2::tests::twist_points;
+    use crate::g2::{G2Affine, G2PairingAffine};
+
+    #[test]
+    fn g1_not_on_curve_test() {
+        let mut g1 = G1Affine::generator();
+        g1.y += Fq::one();
+        let g2 = G2PairingAffine::from(G2Affine::generator());
+
+        assert_eq!(
+            AteParing::try_multi_miller_loop(&[(&G1Affine::generator(), &g2), (&g1, &g2)]),
+            Err(PairingError::G1NotOnCurve)
+        );
+    }
+
+    #[test]
+    fn malformed_g2_prepared_test() {
+        let g1 = G1Affine::generator();
+        let g2 = G2PairingAffine::from(G2Affine::generator());
+        let identity = G2PairingAffine::from(G2Affine::identity());
+        assert!(AteParing::try_multi_miller_loop(&[(&g1, &g2), (&g1, &identity)]).is_ok());
+
+        let mut off_curve = g2.clone();
+        off_curve.point.y += Fq2::one();
+        let outside = twist_points().map(G2PairingAffine::from);
+
+        for malformed in outside.chain([off_curve]) {
+            assert_eq!(
+                AteParing::try_multi_miller_loop(&[(&g1, &g2), (&g1, &malformed)]),
+                Err(PairingError::MalformedG2Prepared)
+            );
+        }
+    }
 }
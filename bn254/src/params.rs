@@ -1,7 +1,16 @@
 use crate::fq::Fq;
 use crate::fq2::Fq2;
 
-pub(crate) const BN_X: u64 = 4965661367192848881;
+/// Non-adjacent form of the BN parameter x = 4965661367192848881, least
+/// significant digit first
+pub(crate) const BN_X_NAF: [i8; 63] = [
+    1, 0, 0, 0, -1, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 0, 1, 0, -1, 0, 1, 0, 1, 0, 1, 0, 0, 1,
+    0, 0, 0, 1, 0, -1, 0, -1, 0, -1, 0, 1, 0, 1, 0, 0, -1, 0, 1, 0, 1, 0, -1, 0, 0, 1, 0, 1, 0, 0,
+    0, 1,
+];
+
+/// 6x^2, the eigenvalue of ψ on the order r subgroup of the twist
+pub(crate) const BN_6X2: u128 = 0x6f4d8248eeb859fbf83e9682e87cfd46;
 
 pub(crate) const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
     // Fq(-1)**(((q^0) - 1) / 2)
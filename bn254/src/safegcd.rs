@@ -0,0 +1,201 @@
+//! Constant time modular inversion by Bernstein–Yang divsteps.
+//!
+//! "Fast constant-time gcd computation and modular inversion",
+//! https://eprint.iacr.org/2019/266.pdf
+//!
+//! Adaptation of the 62-bit signed limb variant in libsecp256k1's modinv64,
+//! which runs a fixed 10 × 59 = 590 divsteps, enough for any modulus below
+//! 2^256. Values are held in five signed limbs of 62 bits each.
+
+const M62: u64 = u64::MAX >> 2;
+
+type Signed62 = [i64; 5];
+
+/// Transition matrix of 59 divsteps, scaled by 2^62.
+struct Trans2x2 {
+    u: i64,
+    v: i64,
+    q: i64,
+    r: i64,
+}
+
+#[inline(always)]
+const fn to_signed62(a: [u64; 4]) -> Signed62 {
+    [
+        (a[0] & M62) as i64,
+        ((a[0] >> 62 | a[1] << 2) & M62) as i64,
+        ((a[1] >> 60 | a[2] << 4) & M62) as i64,
+        ((a[2] >> 58 | a[3] << 6) & M62) as i64,
+        (a[3] >> 56) as i64,
+    ]
+}
+
+#[inline(always)]
+const fn from_signed62(a: Signed62) -> [u64; 4] {
+    let a = [
+        a[0] as u64,
+        a[1] as u64,
+        a[2] as u64,
+        a[3] as u64,
+        a[4] as u64,
+    ];
+    [
+        a[0] | a[1] << 62,
+        a[1] >> 2 | a[2] << 60,
+        a[2] >> 4 | a[3] << 58,
+        a[3] >> 6 | a[4] << 56,
+    ]
+}
+
+/// Run 59 branch free divsteps on the low limbs of f and g, starting from
+/// `zeta = -(delta + 1/2)`, and return the new zeta with the transition matrix.
+#[inline(always)]
+fn divsteps_59(mut zeta: i64, f0: u64, g0: u64) -> (i64, Trans2x2) {
+    // u, v, q, r start as the identity scaled by 8 so the final matrix
+    // comes out scaled by 2^62
+    let (mut u, mut v, mut q, mut r) = (8u64, 0u64, 0u64, 8u64);
+    let (mut f, mut g) = (f0, g0);
+
+    for _ in 3..62 {
+        // masks for zeta < 0 and for g odd
+        let c1 = (zeta >> 63) as u64;
+        let c2 = (g & 1).wrapping_neg();
+        // conditionally negated f, u, v
+        let x = (f ^ c1).wrapping_sub(c1);
+        let y = (u ^ c1).wrapping_sub(c1);
+        let z = (v ^ c1).wrapping_sub(c1);
+        // conditionally add them to g, q, r
+        g = g.wrapping_add(x & c2);
+        q = q.wrapping_add(y & c2);
+        r = r.wrapping_add(z & c2);
+        // swap condition: zeta < 0 and g odd
+        let c1 = c1 & c2;
+        // zeta becomes -zeta - 2 or zeta - 1
+        zeta = (zeta ^ c1 as i64) - 1;
+        // conditionally add g, q, r to f, u, v
+        f = f.wrapping_add(g & c1);
+        u = u.wrapping_add(q & c1);
+        v = v.wrapping_add(r & c1);
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+    }
+
+    (
+        zeta,
+        Trans2x2 {
+            u: u as i64,
+            v: v as i64,
+            q: q as i64,
+            r: r as i64,
+        },
+    )
+}
+
+/// Compute `(t * [d, e] + modulus * [md, me]) / 2^62` with md, me chosen so
+/// the division is exact, keeping d and e in the range (-2 * modulus, modulus).
+#[inline(always)]
+fn update_de(d: &mut Signed62, e: &mut Signed62, t: &Trans2x2, modulus: &Signed62, inv62: u64) {
+    let (u, v, q, r) = (t.u as i128, t.v as i128, t.q as i128, t.r as i128);
+    // md, me start as [u, q] if d is negative plus [v, r] if e is negative
+    let sd = d[4] >> 63;
+    let se = e[4] >> 63;
+    let mut md = (t.u & sd) + (t.v & se);
+    let mut me = (t.q & sd) + (t.r & se);
+
+    let mut cd = u * d[0] as i128 + v * e[0] as i128;
+    let mut ce = q * d[0] as i128 + r * e[0] as i128;
+
+    // correct md, me so the bottom 62 bits of the result vanish
+    md -= (inv62.wrapping_mul(cd as u64).wrapping_add(md as u64) & M62) as i64;
+    me -= (inv62.wrapping_mul(ce as u64).wrapping_add(me as u64) & M62) as i64;
+
+    cd += modulus[0] as i128 * md as i128;
+    ce += modulus[0] as i128 * me as i128;
+    cd >>= 62;
+    ce >>= 62;
+
+    for i in 1..5 {
+        cd += u * d[i] as i128 + v * e[i] as i128 + modulus[i] as i128 * md as i128;
+        ce += q * d[i] as i128 + r * e[i] as i128 + modulus[i] as i128 * me as i128;
+        d[i - 1] = (cd as u64 & M62) as i64;
+        e[i - 1] = (ce as u64 & M62) as i64;
+        cd >>= 62;
+        ce >>= 62;
+    }
+    d[4] = cd as i64;
+    e[4] = ce as i64;
+}
+
+/// Compute `t * [f, g] / 2^62`, which is exact by construction of t.
+#[inline(always)]
+fn update_fg(f: &mut Signed62, g: &mut Signed62, t: &Trans2x2) {
+    let (u, v, q, r) = (t.u as i128, t.v as i128, t.q as i128, t.r as i128);
+
+    let mut cf = u * f[0] as i128 + v * g[0] as i128;
+    let mut cg = q * f[0] as i128 + r * g[0] as i128;
+    cf >>= 62;
+    cg >>= 62;
+
+    for i in 1..5 {
+        cf += u * f[i] as i128 + v * g[i] as i128;
+        cg += q * f[i] as i128 + r * g[i] as i128;
+        f[i - 1] = (cf as u64 & M62) as i64;
+        g[i - 1] = (cg as u64 & M62) as i64;
+        cf >>= 62;
+        cg >>= 62;
+    }
+    f[4] = cf as i64;
+    g[4] = cg as i64;
+}
+
+/// Bring r from (-2 * modulus, modulus) to [0, modulus), negating it first
+/// when `sign` is negative.
+#[inline(always)]
+fn normalize(r: &mut Signed62, sign: i64, modulus: &Signed62) {
+    let propagate = |r: &mut Signed62| {
+        for i in 0..4 {
+            r[i + 1] += r[i] >> 62;
+            r[i] &= M62 as i64;
+        }
+    };
+
+    let cond_add = r[4] >> 63;
+    for (r, m) in r.iter_mut().zip(modulus) {
+        *r += m & cond_add;
+    }
+    let cond_negate = sign >> 63;
+    for r in r.iter_mut() {
+        *r = (*r ^ cond_negate) - cond_negate;
+    }
+    propagate(r);
+
+    let cond_add = r[4] >> 63;
+    for (r, m) in r.iter_mut().zip(modulus) {
+        *r += m & cond_add;
+    }
+    propagate(r);
+}
+
+/// Inverse of the canonical integer `a` modulo `p`, or zero when `a` is zero.
+/// `inv62` is `p^{-1} mod 2^62`. The running time depends only on `p`.
+pub(crate) fn invert(a: [u64; 4], p: [u64; 4], inv62: u64) -> [u64; 4] {
+    let modulus = to_signed62(p);
+    let mut d = [0i64; 5];
+    let mut e = [1i64, 0, 0, 0, 0];
+    let mut f = modulus;
+    let mut g = to_signed62(a);
+    // zeta = -(delta + 1/2) with delta starting at 1/2
+    let mut zeta = -1;
+
+    for _ in 0..10 {
+        let (z, t) = divsteps_59(zeta, f[0] as u64, g[0] as u64);
+        zeta = z;
+        update_de(&mut d, &mut e, &t, &modulus, inv62);
+        update_fg(&mut f, &mut g, &t);
+    }
+
+    // g is now zero and f is +-gcd(a, p) = +-1, whose sign fixes up d
+    normalize(&mut d, f[4], &modulus);
+    from_signed62(d)
+}
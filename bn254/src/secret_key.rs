@@ -0,0 +1,135 @@
+//! Secret scalars that are wiped when dropped.
+use core::fmt;
+use core::ops::Mul;
+use rand_core::RngCore;
+use subtle::{Choice, ConstantTimeEq, CtOption};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::fixed_base::FixedBaseTable;
+use crate::fr::Fr;
+use crate::g1::{G1Affine, G1Projective};
+use crate::g2::{G2Affine, G2Projective};
+
+/// Errors turning a scalar into a `SecretKey`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecretKeyError {
+    /// The scalar is zero, which is not a valid key.
+    Zero,
+}
+
+impl fmt::Display for SecretKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Zero => write!(f, "secret key is zero"),
+        }
+    }
+}
+
+/// A non-zero secret scalar, such as a signing key or a threshold share of
+/// one.
+///
+/// Unlike `Fr` it is neither `Copy` nor `Clone`, so every copy of the
+/// scalar is an explicit `expose_secret`, its `Debug` output is redacted and
+/// it is overwritten with zeros on drop. Points multiply by a reference to
+/// it, which hands the scalar to the constant time multiplication without
+/// moving the key.
+#[repr(transparent)]
+pub struct SecretKey(Fr);
+
+impl SecretKey {
+    /// Uniformly random non-zero key.
+    pub fn random<R: RngCore>(rand: &mut R) -> Self {
+        loop {
+            let key = Self(Fr::random(rand));
+            if !bool::from(key.0.is_zero()) {
+                return key;
+            }
+        }
+    }
+
+    /// Decode the big-endian scalar, rejecting values not below the modulus
+    /// and zero.
+    pub fn from_bytes(bytes: &[u8; 32]) -> CtOption<Self> {
+        let k = Fr::from_bytes_be(bytes);
+        let is_canonical = k.is_some();
+        let key = Self(k.unwrap_or(Fr::zero()));
+        let is_valid = is_canonical & !key.0.is_zero();
+        CtOption::new(key, is_valid)
+    }
+
+    /// Big-endian encoding, wiped when the returned buffer is dropped.
+    pub fn to_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0.to_bytes_be())
+    }
+
+    /// The scalar itself. The caller owns whatever copy it makes.
+    pub fn expose_secret(&self) -> &Fr {
+        &self.0
+    }
+}
+
+impl TryFrom<Fr> for SecretKey {
+    type Error = SecretKeyError;
+
+    /// Take ownership of a scalar, rejecting zero.
+    fn try_from(k: Fr) -> Result<Self, SecretKeyError> {
+        let key = Self(k);
+        if bool::from(key.0.is_zero()) {
+            return Err(SecretKeyError::Zero);
+        }
+        Ok(key)
+    }
+}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey(<redacted>)")
+    }
+}
+
+impl ConstantTimeEq for SecretKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretKey {}
+
+macro_rules! mul_secret_key {
+    ($lhs:ty, $output:ty) => {
+        impl Mul<&SecretKey> for $lhs {
+            type Output = $output;
+
+            fn mul(self, rhs: &SecretKey) -> $output {
+                self * rhs.0
+            }
+        }
+    };
+}
+
+mul_secret_key!(G1Affine, G1Projective);
+mul_secret_key!(G1Projective, G1Projective);
+mul_secret_key!(G2Affine, G2Projective);
+mul_secret_key!(G2Projective, G2Projective);
+mul_secret_key!(&FixedBaseTable<G1Affine>, G1Projective);
+mul_secret_key!(&FixedBaseTable<G2Affine>, G2Projective);
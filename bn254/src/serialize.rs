@@ -0,0 +1,112 @@
+//! serde support over the compressed encodings. Human-readable formats such
+//! as JSON get the encoding as a hex string, binary formats such as bincode
+//! or CBOR its raw bytes, and deserializing runs the same validation as the
+//! `from_compressed` functions.
+use core::fmt;
+use serde::de::{self, Deserializer, SeqAccess, Unexpected, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::fr::Fr;
+use crate::g1::G1Affine;
+use crate::g2::{G2Affine, G2PairingAffine};
+use crate::gt::Gt;
+
+/// Longest encoding, the one of `Gt`
+const MAX_LEN: usize = 192;
+
+fn serialize_bytes<S: Serializer, const N: usize>(
+    bytes: &[u8; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut buf = [0; 2 * MAX_LEN];
+        let hex = &mut buf[..2 * N];
+        hex::encode_to_slice(bytes, hex).expect("the buffer fits every encoding");
+        serializer.serialize_str(core::str::from_utf8(hex).expect("hex digits are ascii"))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// Accepts hex with an optional `0x` prefix, raw bytes, and for formats
+/// without a byte string type a sequence of exactly `N` bytes.
+struct BytesVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes or {} hex digits", N, 2 * N)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<[u8; N], E> {
+        let digits = v.strip_prefix("0x").unwrap_or(v);
+        let mut bytes = [0; N];
+        hex::decode_to_slice(digits, &mut bytes)
+            .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))?;
+        Ok(bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<[u8; N], E> {
+        v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+        let mut bytes = [0; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(bytes)
+    }
+}
+
+macro_rules! serde_impl {
+    ($t:ty, $len:expr, $to:ident, $from:ident) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_bytes(&self.$to(), serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes::<D, $len>(deserializer)?;
+                Option::from(<$t>::$from(&bytes))
+                    .ok_or_else(|| de::Error::custom(concat!("invalid ", stringify!($t))))
+            }
+        }
+    };
+}
+
+serde_impl!(Fr, 32, to_bytes_be, from_bytes_be);
+serde_impl!(G1Affine, 32, to_compressed, from_compressed);
+serde_impl!(G2Affine, 64, to_compressed, from_compressed);
+serde_impl!(Gt, 192, to_compressed, from_compressed);
+
+/// Encoded as its G2 point, and prepared again on the way back.
+impl Serialize for G2PairingAffine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.point.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for G2PairingAffine {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        G2Affine::deserialize(deserializer).map(Self::from)
+    }
+}
@@ -0,0 +1,673 @@
+//! The zkcrypto `ff`, `group` and `pairing` traits, so the crate drops into
+//! code generic over them such as halo2 and bellman circuits.
+//!
+//! Points encode as the uncompressed big-endian `x || y` of EIP-196, and
+//! `Bn254` is the `Engine` over `AteParing`, with `G2PairingAffine` as the
+//! prepared G2 point.
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use ff::{Field, FromUniformBytes, PrimeField, WithSmallOrderMulGroup};
+use group::prime::{PrimeCurve, PrimeCurveAffine, PrimeGroup};
+use group::{Curve, Group, GroupEncoding, UncompressedEncoding};
+use pairing::{Engine, MillerLoopResult, MultiMillerLoop, PairingCurveAffine};
+use rand_core::RngCore;
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+use crate::fq::Fq;
+use crate::fq12::Fq12;
+use crate::fr::{self, Fr};
+use crate::g1::{G1Affine, G1Projective};
+use crate::g2::{G2Affine, G2PairingAffine, G2Projective};
+use crate::gt::Gt;
+use crate::limbs::from_le_bytes;
+use crate::pairing::AteParing;
+
+/// The operators on references and the iterator sums and products the
+/// `Field` bound asks for, in terms of the owned operators.
+macro_rules! field_ops {
+    ($f:ty) => {
+        impl<'a> Add<&'a $f> for $f {
+            type Output = $f;
+
+            fn add(self, rhs: &'a $f) -> $f {
+                self + *rhs
+            }
+        }
+
+        impl<'a> Sub<&'a $f> for $f {
+            type Output = $f;
+
+            fn sub(self, rhs: &'a $f) -> $f {
+                self - *rhs
+            }
+        }
+
+        impl<'a> Mul<&'a $f> for $f {
+            type Output = $f;
+
+            fn mul(self, rhs: &'a $f) -> $f {
+                self * *rhs
+            }
+        }
+
+        impl<'a> AddAssign<&'a $f> for $f {
+            fn add_assign(&mut self, rhs: &'a $f) {
+                *self += *rhs;
+            }
+        }
+
+        impl<'a> SubAssign<&'a $f> for $f {
+            fn sub_assign(&mut self, rhs: &'a $f) {
+                *self -= *rhs;
+            }
+        }
+
+        impl<'a> MulAssign<&'a $f> for $f {
+            fn mul_assign(&mut self, rhs: &'a $f) {
+                *self *= *rhs;
+            }
+        }
+
+        impl Sum for $f {
+            fn sum<I: Iterator<Item = $f>>(iter: I) -> $f {
+                iter.fold(<$f>::zero(), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> Sum<&'a $f> for $f {
+            fn sum<I: Iterator<Item = &'a $f>>(iter: I) -> $f {
+                iter.fold(<$f>::zero(), |acc, x| acc + x)
+            }
+        }
+
+        impl Product for $f {
+            fn product<I: Iterator<Item = $f>>(iter: I) -> $f {
+                iter.fold(<$f>::one(), |acc, x| acc * x)
+            }
+        }
+
+        impl<'a> Product<&'a $f> for $f {
+            fn product<I: Iterator<Item = &'a $f>>(iter: I) -> $f {
+                iter.fold(<$f>::one(), |acc, x| acc * x)
+            }
+        }
+
+        impl From<u64> for $f {
+            fn from(val: u64) -> $f {
+                <$f>::from_u64(val)
+            }
+        }
+    };
+}
+
+/// The operators on references, scalar multiplication by reference and the
+/// iterator sums the `Group` bound asks for.
+macro_rules! group_ops {
+    ($g:ty) => {
+        impl<'a> Add<&'a $g> for $g {
+            type Output = $g;
+
+            fn add(self, rhs: &'a $g) -> $g {
+                self + *rhs
+            }
+        }
+
+        impl<'a> Sub<&'a $g> for $g {
+            type Output = $g;
+
+            fn sub(self, rhs: &'a $g) -> $g {
+                self - *rhs
+            }
+        }
+
+        impl<'a> AddAssign<&'a $g> for $g {
+            fn add_assign(&mut self, rhs: &'a $g) {
+                *self += *rhs;
+            }
+        }
+
+        impl<'a> SubAssign<&'a $g> for $g {
+            fn sub_assign(&mut self, rhs: &'a $g) {
+                *self -= *rhs;
+            }
+        }
+
+        impl<'a> Mul<&'a Fr> for $g {
+            type Output = $g;
+
+            fn mul(self, rhs: &'a Fr) -> $g {
+                self * *rhs
+            }
+        }
+
+        impl<'a> MulAssign<&'a Fr> for $g {
+            fn mul_assign(&mut self, rhs: &'a Fr) {
+                *self *= *rhs;
+            }
+        }
+
+        impl Sum for $g {
+            fn sum<I: Iterator<Item = $g>>(iter: I) -> $g {
+                iter.fold(<$g>::identity(), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> Sum<&'a $g> for $g {
+            fn sum<I: Iterator<Item = &'a $g>>(iter: I) -> $g {
+                iter.fold(<$g>::identity(), |acc, x| acc + x)
+            }
+        }
+    };
+}
+
+/// The mixed operators on affine references, and scalar multiplication of
+/// an affine point by reference.
+macro_rules! affine_ops {
+    ($p:ty, $a:ty) => {
+        impl<'a> Add<&'a $a> for $p {
+            type Output = $p;
+
+            fn add(self, rhs: &'a $a) -> $p {
+                self + *rhs
+            }
+        }
+
+        impl<'a> Sub<&'a $a> for $p {
+            type Output = $p;
+
+            fn sub(self, rhs: &'a $a) -> $p {
+                self - *rhs
+            }
+        }
+
+        impl<'a> AddAssign<&'a $a> for $p {
+            fn add_assign(&mut self, rhs: &'a $a) {
+                *self += *rhs;
+            }
+        }
+
+        impl<'a> SubAssign<&'a $a> for $p {
+            fn sub_assign(&mut self, rhs: &'a $a) {
+                *self -= *rhs;
+            }
+        }
+
+        impl<'a> Mul<&'a Fr> for $a {
+            type Output = $p;
+
+            fn mul(self, rhs: &'a Fr) -> $p {
+                self * *rhs
+            }
+        }
+    };
+}
+
+/// Uncompressed encoding of a byte array, which `Default` and `AsRef` need
+/// wrapped above 32 bytes.
+macro_rules! uncompressed {
+    ($(#[$doc:meta])* $name:ident, $len:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct $name(pub [u8; $len]);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self([0; $len])
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl AsMut<[u8]> for $name {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+    };
+}
+
+/// The affine and projective point traits, over the curve's own encodings.
+macro_rules! curve_impls {
+    ($p:ty, $a:ty, $repr:ident) => {
+        impl Group for $p {
+            type Scalar = Fr;
+
+            fn random(mut rng: impl RngCore) -> Self {
+                <$p>::generator() * Fr::random(&mut rng)
+            }
+
+            fn identity() -> Self {
+                <$p>::identity()
+            }
+
+            fn generator() -> Self {
+                <$p>::generator()
+            }
+
+            fn is_identity(&self) -> Choice {
+                self.z.is_zero()
+            }
+
+            fn double(&self) -> Self {
+                <$p>::double(*self)
+            }
+        }
+
+        impl Curve for $p {
+            type AffineRepr = $a;
+
+            fn batch_normalize(p: &[Self], q: &mut [$a]) {
+                <$p>::batch_normalize(p, q);
+            }
+
+            fn to_affine(&self) -> $a {
+                <$a>::from(*self)
+            }
+        }
+
+        impl GroupEncoding for $p {
+            type Repr = $repr;
+
+            fn from_bytes(bytes: &$repr) -> CtOption<Self> {
+                let p = <$a>::from_bytes(bytes);
+                CtOption::new(p.unwrap_or(<$a>::identity()).into(), p.is_some())
+            }
+
+            fn from_bytes_unchecked(bytes: &$repr) -> CtOption<Self> {
+                let p = <$a>::from_bytes_unchecked(bytes);
+                CtOption::new(p.unwrap_or(<$a>::identity()).into(), p.is_some())
+            }
+
+            fn to_bytes(&self) -> $repr {
+                <$a>::from(*self).to_bytes()
+            }
+        }
+
+        impl PrimeGroup for $p {}
+
+        impl PrimeCurve for $p {
+            type Affine = $a;
+        }
+
+        impl PrimeCurveAffine for $a {
+            type Scalar = Fr;
+            type Curve = $p;
+
+            fn identity() -> Self {
+                <$a>::identity()
+            }
+
+            fn generator() -> Self {
+                <$a>::generator()
+            }
+
+            fn is_identity(&self) -> Choice {
+                self.ct_is_identity()
+            }
+
+            fn to_curve(&self) -> $p {
+                <$p>::from(*self)
+            }
+        }
+
+        impl GroupEncoding for $a {
+            type Repr = $repr;
+
+            fn from_bytes(bytes: &$repr) -> CtOption<Self> {
+                <$a>::from_uncompressed(&bytes.0)
+            }
+
+            /// The checks are cheap next to the rest of a pairing-based
+            /// protocol, so this is `from_bytes`.
+            fn from_bytes_unchecked(bytes: &$repr) -> CtOption<Self> {
+                <$a>::from_uncompressed(&bytes.0)
+            }
+
+            fn to_bytes(&self) -> $repr {
+                $repr(<$a>::to_uncompressed(self))
+            }
+        }
+
+        impl UncompressedEncoding for $a {
+            type Uncompressed = $repr;
+
+            fn from_uncompressed(bytes: &$repr) -> CtOption<Self> {
+                <$a>::from_uncompressed(&bytes.0)
+            }
+
+            fn from_uncompressed_unchecked(bytes: &$repr) -> CtOption<Self> {
+                <$a>::from_uncompressed(&bytes.0)
+            }
+
+            fn to_uncompressed(&self) -> $repr {
+                $repr(<$a>::to_uncompressed(self))
+            }
+        }
+    };
+}
+
+field_ops!(Fr);
+field_ops!(Fq);
+group_ops!(G1Projective);
+group_ops!(G2Projective);
+group_ops!(Gt);
+affine_ops!(G1Projective, G1Affine);
+affine_ops!(G2Projective, G2Affine);
+
+uncompressed!(
+    /// The 64-byte `x || y` encoding of a G1 point.
+    G1Uncompressed,
+    64
+);
+uncompressed!(
+    /// The 128-byte `x || y` encoding of a G2 point, each coordinate as
+    /// `c1 || c0`.
+    G2Uncompressed,
+    128
+);
+
+curve_impls!(G1Projective, G1Affine, G1Uncompressed);
+curve_impls!(G2Projective, G2Affine, G2Uncompressed);
+
+impl Field for Fr {
+    const ZERO: Self = Fr::zero();
+    const ONE: Self = Fr::one();
+
+    fn random(mut rng: impl RngCore) -> Self {
+        Fr::random(&mut rng)
+    }
+
+    fn is_zero(&self) -> Choice {
+        Fr::is_zero(*self)
+    }
+
+    fn square(&self) -> Self {
+        Fr::square(*self)
+    }
+
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        Fr::invert(*self)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        Fr::sqrt(*self)
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        ff::helpers::sqrt_ratio_generic(num, div)
+    }
+}
+
+impl PrimeField for Fr {
+    /// Little-endian canonical bytes.
+    type Repr = [u8; 32];
+
+    fn from_repr(repr: [u8; 32]) -> CtOption<Self> {
+        Fr::from_bytes_le(&repr)
+    }
+
+    fn to_repr(&self) -> [u8; 32] {
+        self.to_bytes_le()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_bytes_le()[0] & 1)
+    }
+
+    const MODULUS: &'static str =
+        "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+    const NUM_BITS: u32 = 254;
+    const CAPACITY: u32 = 253;
+    const TWO_INV: Self = Fr::to_mont_form([
+        0xa1f0fac9f8000001,
+        0x9419f4243cdcb848,
+        0xdc2822db40c0ac2e,
+        0x183227397098d014,
+    ]);
+    const MULTIPLICATIVE_GENERATOR: Self = Fr::from_u64(5);
+    const S: u32 = 28;
+    const ROOT_OF_UNITY: Self = fr::ROOT_OF_UNITY;
+    /// 5^-t
+    const ROOT_OF_UNITY_INV: Self = Fr::to_mont_form([
+        0x723942779d18157e,
+        0xec9d51f8fd399d5d,
+        0x6117635d49d5387f,
+        0x01b775199c229cd5,
+    ]);
+    /// 5^(2^S)
+    const DELTA: Self = Fr::to_mont_form([
+        0xa1458af683d28e01,
+        0x3f544c14cc40ac6b,
+        0x21f1ec72be9f9a26,
+        0x0ba49465f79ce3ca,
+    ]);
+}
+
+impl WithSmallOrderMulGroup<3> for Fr {
+    /// λ - 1 for the GLV eigenvalue λ, a primitive cube root of unity
+    const ZETA: Self = Fr::to_mont_form([
+        0xb8ca0b2d36636f23,
+        0xcc37a73fec2bc5e9,
+        0x048b6e193fd84104,
+        0x30644e72e131a029,
+    ]);
+}
+
+impl FromUniformBytes<64> for Fr {
+    /// Reduce 64 little-endian bytes mod r, with a bias of at most 2^-258.
+    fn from_uniform_bytes(bytes: &[u8; 64]) -> Self {
+        let (lo, hi) = bytes.split_at(32);
+        let lo = from_le_bytes(lo.try_into().unwrap());
+        let hi = from_le_bytes(hi.try_into().unwrap());
+        Fr::from_u512(lo, hi)
+    }
+}
+
+impl Field for Fq {
+    const ZERO: Self = Fq::zero();
+    const ONE: Self = Fq::one();
+
+    fn random(mut rng: impl RngCore) -> Self {
+        Fq::random(&mut rng)
+    }
+
+    fn is_zero(&self) -> Choice {
+        Fq::is_zero(*self)
+    }
+
+    fn square(&self) -> Self {
+        Fq::square(*self)
+    }
+
+    fn double(&self) -> Self {
+        Fq::double(*self)
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        Fq::invert(*self)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        Fq::sqrt(*self)
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        ff::helpers::sqrt_ratio_generic(num, div)
+    }
+}
+
+impl PrimeField for Fq {
+    /// Little-endian canonical bytes.
+    type Repr = [u8; 32];
+
+    fn from_repr(repr: [u8; 32]) -> CtOption<Self> {
+        Fq::from_bytes_le(&repr)
+    }
+
+    fn to_repr(&self) -> [u8; 32] {
+        self.to_bytes_le()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_bytes_le()[0] & 1)
+    }
+
+    const MODULUS: &'static str =
+        "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
+    const NUM_BITS: u32 = 254;
+    const CAPACITY: u32 = 253;
+    const TWO_INV: Self = Fq::to_mont_form([
+        0x9e10460b6c3e7ea4,
+        0xcbc0b548b438e546,
+        0xdc2822db40c0ac2e,
+        0x183227397098d014,
+    ]);
+    const MULTIPLICATIVE_GENERATOR: Self = Fq::from_u64(3);
+    /// q = 3 mod 4
+    const S: u32 = 1;
+    /// -1
+    const ROOT_OF_UNITY: Self = Fq::to_mont_form([
+        0x3c208c16d87cfd46,
+        0x97816a916871ca8d,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ]);
+    const ROOT_OF_UNITY_INV: Self = Self::ROOT_OF_UNITY;
+    /// 3^2
+    const DELTA: Self = Fq::from_u64(9);
+}
+
+impl WithSmallOrderMulGroup<3> for Fq {
+    /// β of the G1 endomorphism, a primitive cube root of unity
+    const ZETA: Self = Fq::to_mont_form([
+        0x5763473177fffffe,
+        0xd4f263f1acdb5c4f,
+        0x59e26bcea0d48bac,
+        0,
+    ]);
+}
+
+impl Group for Gt {
+    type Scalar = Fr;
+
+    fn random(mut rng: impl RngCore) -> Self {
+        Gt::random(&mut rng)
+    }
+
+    fn identity() -> Self {
+        Gt::identity()
+    }
+
+    fn generator() -> Self {
+        Gt::generator()
+    }
+
+    fn is_identity(&self) -> Choice {
+        self.ct_eq(&Gt::identity())
+    }
+
+    fn double(&self) -> Self {
+        *self + *self
+    }
+}
+
+impl PairingCurveAffine for G1Affine {
+    type Pair = G2Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &G2Affine) -> Gt {
+        AteParing::pairing(*self, *other)
+    }
+}
+
+impl PairingCurveAffine for G2Affine {
+    type Pair = G1Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &G1Affine) -> Gt {
+        AteParing::pairing(*other, *self)
+    }
+}
+
+/// The BN254 optimal ate pairing as a `pairing::Engine`.
+#[derive(Clone, Copy, Debug)]
+pub struct Bn254;
+
+impl Engine for Bn254 {
+    type Fr = Fr;
+    type G1 = G1Projective;
+    type G1Affine = G1Affine;
+    type G2 = G2Projective;
+    type G2Affine = G2Affine;
+    type Gt = Gt;
+
+    fn pairing(p: &G1Affine, q: &G2Affine) -> Gt {
+        AteParing::pairing(*p, *q)
+    }
+}
+
+impl MultiMillerLoop for Bn254 {
+    type G2Prepared = G2PairingAffine;
+    type Result = MillerLoopOutput;
+
+    fn multi_miller_loop(terms: &[(&G1Affine, &G2PairingAffine)]) -> MillerLoopOutput {
+        MillerLoopOutput(AteParing::multi_miller_loop_refs(terms))
+    }
+}
+
+/// The `Fq12` value of a Miller loop before the final exponentiation.
+/// Results combine multiplicatively, which the `MillerLoopResult` trait
+/// writes as addition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MillerLoopOutput(pub Fq12);
+
+impl Default for MillerLoopOutput {
+    fn default() -> Self {
+        Self(Fq12::one())
+    }
+}
+
+impl Add for MillerLoopOutput {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl<'a> Add<&'a MillerLoopOutput> for MillerLoopOutput {
+    type Output = Self;
+
+    fn add(self, rhs: &'a MillerLoopOutput) -> Self {
+        self + *rhs
+    }
+}
+
+impl AddAssign for MillerLoopOutput {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<'a> AddAssign<&'a MillerLoopOutput> for MillerLoopOutput {
+    fn add_assign(&mut self, rhs: &'a MillerLoopOutput) {
+        *self = *self + *rhs;
+    }
+}
+
+impl MillerLoopResult for MillerLoopOutput {
+    type Gt = Gt;
+
+    fn final_exponentiation(&self) -> Gt {
+        self.0.final_exp()
+    }
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "ark")]
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, UniformRand};
+use bn254::{ArkError, AteParing, Fq, Fq12, Fq2, Fr, G1Affine, G2Affine, Gt};
+use rand_core::OsRng;
+
+const ITERATIONS: usize = 50;
+
+/// arkworks' final exponentiation has an extra factor 2x(6x^2 + 3x + 1).
+const ARK_FINAL_EXP_FACTOR: [u64; 4] = [
+    0x2e5d4e223ddedaf4,
+    0x1ea96b02d9d9e38d,
+    0x3bec47df15e307c8,
+    0,
+];
+
+#[test]
+fn field_round_trip_test() {
+    let mut rng = OsRng;
+    for _ in 0..ITERATIONS {
+        let a = Fq::random(&mut rng);
+        assert_eq!(Fq::from(ark_bn254::Fq::from(a)), a);
+        let a = Fr::random(&mut rng);
+        assert_eq!(Fr::from(ark_bn254::Fr::from(a)), a);
+        let a = Fq12::random(&mut rng);
+        assert_eq!(Fq12::from(ark_bn254::Fq12::from(a)), a);
+
+        let x = ark_bn254::Fq::rand(&mut rng);
+        assert_eq!(ark_bn254::Fq::from(Fq::from(x)), x);
+        let x = ark_bn254::Fr::rand(&mut rng);
+        assert_eq!(ark_bn254::Fr::from(Fr::from(x)), x);
+    }
+
+    // the conversions agree on arithmetic, not just on the encoding
+    let (a, b) = (Fq2::random(&mut rng), Fq2::random(&mut rng));
+    let (x, y) = (ark_bn254::Fq2::from(a), ark_bn254::Fq2::from(b));
+    assert_eq!(ark_bn254::Fq2::from(a * b), x * y);
+    assert_eq!(
+        ark_bn254::Fq2::from(a.invert().unwrap()),
+        x.inverse().unwrap()
+    );
+    assert_eq!(ark_bn254::Fq::from(-Fq::one()), -ark_bn254::Fq::ONE);
+    assert_eq!(ark_bn254::Fr::from(-Fr::one()), -ark_bn254::Fr::ONE);
+}
+
+#[test]
+fn g1_round_trip_test() {
+    let mut rng = OsRng;
+    assert_eq!(
+        ark_bn254::G1Affine::from(G1Affine::generator()),
+        ark_bn254::G1Affine::generator()
+    );
+    assert_eq!(
+        ark_bn254::G1Affine::from(G1Affine::identity()),
+        ark_bn254::G1Affine::identity()
+    );
+    assert_eq!(
+        G1Affine::try_from(ark_bn254::G1Affine::identity()),
+        Ok(G1Affine::identity())
+    );
+
+    for _ in 0..ITERATIONS {
+        let k = Fr::random(&mut rng);
+        let p = G1Affine::from(G1Affine::generator() * k);
+        let x = (ark_bn254::G1Affine::generator() * ark_bn254::Fr::from(k)).into_affine();
+        assert_eq!(ark_bn254::G1Affine::from(p), x);
+        assert_eq!(G1Affine::try_from(x), Ok(p));
+    }
+
+    let off_curve = ark_bn254::G1Affine::new_unchecked(ark_bn254::Fq::ONE, ark_bn254::Fq::ONE);
+    assert_eq!(G1Affine::try_from(off_curve), Err(ArkError::NotOnCurve));
+    let zero = ark_bn254::G1Affine::new_unchecked(ark_bn254::Fq::ZERO, ark_bn254::Fq::ZERO);
+    assert_eq!(G1Affine::try_from(zero), Err(ArkError::NotOnCurve));
+}
+
+#[test]
+fn g2_round_trip_test() {
+    let mut rng = OsRng;
+    assert_eq!(
+        ark_bn254::G2Affine::from(G2Affine::generator()),
+        ark_bn254::G2Affine::generator()
+    );
+    assert_eq!(
+        ark_bn254::G2Affine::from(G2Affine::identity()),
+        ark_bn254::G2Affine::identity()
+    );
+    assert_eq!(
+        G2Affine::try_from(ark_bn254::G2Affine::identity()),
+        Ok(G2Affine::identity())
+    );
+
+    for _ in 0..ITERATIONS {
+        let k = Fr::random(&mut rng);
+        let p = G2Affine::from(G2Affine::generator() * k);
+        let x = (ark_bn254::G2Affine::generator() * ark_bn254::Fr::from(k)).into_affine();
+        assert_eq!(ark_bn254::G2Affine::from(p), x);
+        assert_eq!(G2Affine::try_from(x), Ok(p));
+    }
+
+    let off_curve = ark_bn254::G2Affine::new_unchecked(ark_bn254::Fq2::ONE, ark_bn254::Fq2::ONE);
+    assert_eq!(G2Affine::try_from(off_curve), Err(ArkError::NotOnCurve));
+
+    // points of the twist are almost never in the order r subgroup
+    let mut x = ark_bn254::Fq2::ONE;
+    let outside = loop {
+        if let Some(p) = ark_bn254::G2Affine::get_point_from_x_unchecked(x, false) {
+            break p;
+        }
+        x += ark_bn254::Fq2::ONE;
+    };
+    assert!(outside.is_on_curve());
+    assert!(!outside.is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(G2Affine::try_from(outside), Err(ArkError::NotInSubgroup));
+}
+
+#[test]
+fn gt_round_trip_test() {
+    let mut rng = OsRng;
+    for _ in 0..ITERATIONS / 10 {
+        let p = G1Affine::from(G1Affine::generator() * Fr::random(&mut rng));
+        let q = G2Affine::from(G2Affine::generator() * Fr::random(&mut rng));
+        let gt = AteParing::pairing(p, q);
+        let x =
+            ark_bn254::Bn254::pairing(ark_bn254::G1Affine::from(p), ark_bn254::G2Affine::from(q));
+        assert_eq!(PairingOutput::from(Gt(gt.0.pow(ARK_FINAL_EXP_FACTOR))), x);
+        assert_eq!(Gt::try_from(PairingOutput::from(gt)), Ok(gt));
+        assert_eq!(
+            Gt::try_from(x).map(|x| x.0),
+            Ok(gt.0.pow(ARK_FINAL_EXP_FACTOR))
+        );
+    }
+
+    let outside = PairingOutput::<ark_bn254::Bn254>(Fq12::random(&mut rng).into());
+    assert_eq!(Gt::try_from(outside), Err(ArkError::NotInSubgroup));
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "bip39")]
+
+//! The official BIP-39 English vectors, all with the passphrase "TREZOR".
+use bn254::{DerivationError, Mnemonic, MnemonicError, SecretKey};
+use rand_core::OsRng;
+
+const VECTORS: &str = include_str!("fixtures/bip39.json");
+
+fn vectors() -> Vec<(Vec<u8>, String, Vec<u8>)> {
+    let json: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+    json["english"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            let entropy = hex::decode(v[0].as_str().unwrap()).unwrap();
+            let phrase = v[1].as_str().unwrap().to_owned();
+            let seed = hex::decode(v[2].as_str().unwrap()).unwrap();
+            (entropy, phrase, seed)
+        })
+        .collect()
+}
+
+#[test]
+fn bip39_vectors_test() {
+    let vectors = vectors();
+    assert_eq!(vectors.len(), 24);
+    for (entropy, phrase, seed) in vectors {
+        let mnemonic = Mnemonic::from_entropy(&entropy).unwrap();
+        assert_eq!(mnemonic.to_string(), phrase);
+        assert_eq!(mnemonic.word_count(), entropy.len() * 3 / 4);
+
+        let parsed = Mnemonic::parse(&phrase).unwrap();
+        assert_eq!(parsed.entropy(), &entropy[..]);
+        assert_eq!(parsed, mnemonic);
+        assert_eq!(&parsed.to_seed("TREZOR")[..], &seed[..]);
+    }
+}
+
+#[test]
+fn mnemonic_derive_key_test() {
+    // the seed of the first vector is also the first EIP-2333 test seed
+    let (_, phrase, seed) = &vectors()[0];
+    let mnemonic = Mnemonic::parse(phrase).unwrap();
+    let key = mnemonic.derive_key("TREZOR", "m/12381/60/0/0").unwrap();
+    assert_eq!(key, SecretKey::derive_path(seed, "m/12381/60/0/0").unwrap());
+    assert_ne!(key, mnemonic.derive_key("", "m/12381/60/0/0").unwrap());
+    assert_eq!(
+        mnemonic.derive_key("TREZOR", "m/0'").unwrap_err(),
+        DerivationError::InvalidPath
+    );
+}
+
+#[test]
+fn mnemonic_generate_test() {
+    let mut rng = OsRng;
+    for words in [12, 15, 18, 21, 24] {
+        let mnemonic = Mnemonic::generate(&mut rng, words).unwrap();
+        assert_eq!(mnemonic.word_count(), words);
+        assert_eq!(mnemonic.words().count(), words);
+        assert_eq!(Mnemonic::parse(&mnemonic.to_string()).unwrap(), mnemonic);
+    }
+    for words in [0, 3, 11, 13, 23, 25, 27] {
+        assert_eq!(
+            Mnemonic::generate(&mut rng, words).unwrap_err(),
+            MnemonicError::InvalidLength
+        );
+    }
+}
+
+#[test]
+fn mnemonic_parse_errors_test() {
+    let valid = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    assert!(Mnemonic::parse(valid).is_ok());
+    // surrounding and repeated whitespace is accepted
+    assert!(Mnemonic::parse(&format!("  {}\n", valid.replace(' ', " \t "))).is_ok());
+
+    let errors = [
+        ("", MnemonicError::InvalidLength),
+        ("legal winner thank", MnemonicError::InvalidLength),
+        (
+            "legal winner thank year wave sausage worth useful legal winner thank",
+            MnemonicError::InvalidLength,
+        ),
+        (
+            "legal winner thank year wave sausage worth useful legal winner thank yellow yellow",
+            MnemonicError::InvalidLength,
+        ),
+        (
+            "legal winner thank year wave sausage worth useful legal winner thank Yellow",
+            MnemonicError::UnknownWord,
+        ),
+        (
+            "legal winner thank year wave sausage worth useful legal winner thank yello",
+            MnemonicError::UnknownWord,
+        ),
+        (
+            "legal winner thank year wave sausage worth useful legal winner thank year",
+            MnemonicError::InvalidChecksum,
+        ),
+        (
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+            MnemonicError::InvalidChecksum,
+        ),
+    ];
+    for (phrase, error) in errors {
+        assert_eq!(Mnemonic::parse(phrase).unwrap_err(), error, "{phrase}");
+    }
+    let long = ["abandon"; 27].join(" ");
+    assert_eq!(
+        Mnemonic::parse(&long).unwrap_err(),
+        MnemonicError::InvalidLength
+    );
+
+    for len in [0, 12, 15, 17, 33] {
+        assert_eq!(
+            Mnemonic::from_entropy(&vec![0; len]).unwrap_err(),
+            MnemonicError::InvalidLength
+        );
+    }
+}
+
+#[test]
+fn passphrase_is_normalized_test() {
+    // "é" precomposed and as "e" with a combining acute accent
+    let mnemonic = Mnemonic::from_entropy(&[0x7f; 16]).unwrap();
+    assert_eq!(
+        *mnemonic.to_seed("caf\u{e9}"),
+        *mnemonic.to_seed("cafe\u{301}")
+    );
+    assert_ne!(*mnemonic.to_seed("caf\u{e9}"), *mnemonic.to_seed("cafe"));
+}
+
+#[test]
+fn mnemonic_debug_is_redacted_test() {
+    let mnemonic = Mnemonic::from_entropy(&[0; 16]).unwrap();
+    assert_eq!(format!("{mnemonic:?}"), "Mnemonic(<12 words redacted>)");
+}
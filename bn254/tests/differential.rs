@@ -0,0 +1,308 @@
+//! Differential tests against arkworks' independent BN254 implementation.
+//!
+//! Values cross over through the canonical little-endian encodings, which
+//! use the same tower as arkworks, so every hand written constant is
+//! exercised against theirs.
+use ark_bn254::Bn254;
+use ark_ec::pairing::{MillerLoopOutput, Pairing};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField};
+use bn254::{
+    AteParing, Fq, Fq12, Fq2, Fq6, Fr, G1Affine, G1Projective, G2Affine, G2PairingAffine,
+    G2Projective,
+};
+use rand_core::OsRng;
+
+const FIELD_ITERATIONS: usize = 1000;
+const CURVE_ITERATIONS: usize = 50;
+const PAIRING_ITERATIONS: usize = 10;
+/// Curve and pairing iterations of the ignored exhaustive variants, run
+/// with `cargo test --release --test differential -- --ignored`.
+const EXHAUSTIVE_CURVE_ITERATIONS: usize = 1000;
+const EXHAUSTIVE_PAIRING_ITERATIONS: usize = 2000;
+
+/// arkworks raises to 2x(6x^2 + 3x + 1) times (q^12 - 1) / r in its final
+/// exponentiation, while `final_exp` raises to (q^12 - 1) / r exactly.
+const ARK_FINAL_EXP_FACTOR: [u64; 4] = [
+    0x2e5d4e223ddedaf4,
+    0x1ea96b02d9d9e38d,
+    0x3bec47df15e307c8,
+    0,
+];
+
+fn to_ark<F: Field<BasePrimeField = ark_bn254::Fq>>(bytes: &[u8]) -> F {
+    let elems = bytes
+        .chunks(32)
+        .map(ark_bn254::Fq::from_le_bytes_mod_order)
+        .collect::<Vec<_>>();
+    F::from_base_prime_field_elems(&elems).unwrap()
+}
+
+fn ark_fr(k: Fr) -> ark_bn254::Fr {
+    ark_bn254::Fr::from_le_bytes_mod_order(&k.to_bytes_le())
+}
+
+fn ark_g1(p: G1Affine) -> ark_bn254::G1Affine {
+    if p.is_identity() {
+        return ark_bn254::G1Affine::identity();
+    }
+    ark_bn254::G1Affine::new(to_ark(&p.x().to_bytes_le()), to_ark(&p.y().to_bytes_le()))
+}
+
+fn ark_g2(p: G2Affine) -> ark_bn254::G2Affine {
+    if p.is_identity() {
+        return ark_bn254::G2Affine::identity();
+    }
+    ark_bn254::G2Affine::new(to_ark(&p.x().to_bytes_le()), to_ark(&p.y().to_bytes_le()))
+}
+
+fn fq12_from_ark(f: ark_bn254::Fq12) -> Fq12 {
+    let bytes = f
+        .to_base_prime_field_elements()
+        .flat_map(|c| c.into_bigint().to_bytes_le())
+        .collect::<Vec<_>>();
+    Fq12::from_bytes_le(&bytes.try_into().unwrap()).unwrap()
+}
+
+macro_rules! field_test {
+    ($name:ident, $field:ident, $ark:ty) => {
+        #[test]
+        fn $name() {
+            let mut rng = OsRng;
+            for _ in 0..FIELD_ITERATIONS {
+                let a = $field::random(&mut rng);
+                let b = $field::random(&mut rng);
+                let (x, y) = (
+                    to_ark::<$ark>(&a.to_bytes_le()),
+                    to_ark::<$ark>(&b.to_bytes_le()),
+                );
+
+                assert_eq!(to_ark::<$ark>(&(a + b).to_bytes_le()), x + y);
+                assert_eq!(to_ark::<$ark>(&(a - b).to_bytes_le()), x - y);
+                assert_eq!(to_ark::<$ark>(&(-a).to_bytes_le()), -x);
+                assert_eq!(to_ark::<$ark>(&(a * b).to_bytes_le()), x * y);
+                assert_eq!(to_ark::<$ark>(&a.square().to_bytes_le()), x.square());
+                assert_eq!(
+                    to_ark::<$ark>(&a.invert().unwrap().to_bytes_le()),
+                    x.inverse().unwrap()
+                );
+            }
+        }
+    };
+}
+
+field_test!(fq_test, Fq, ark_bn254::Fq);
+field_test!(fq2_test, Fq2, ark_bn254::Fq2);
+field_test!(fq6_test, Fq6, ark_bn254::Fq6);
+field_test!(fq12_test, Fq12, ark_bn254::Fq12);
+
+#[test]
+fn fr_test() {
+    let mut rng = OsRng;
+    for _ in 0..FIELD_ITERATIONS {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let (x, y) = (ark_fr(a), ark_fr(b));
+
+        assert_eq!(ark_fr(a + b), x + y);
+        assert_eq!(ark_fr(a - b), x - y);
+        assert_eq!(ark_fr(a * b), x * y);
+        assert_eq!(ark_fr(a.invert().unwrap()), x.inverse().unwrap());
+    }
+}
+
+#[test]
+fn sqrt_test() {
+    let mut rng = OsRng;
+    for _ in 0..FIELD_ITERATIONS {
+        let a = Fq::random(&mut rng);
+        let x = to_ark::<ark_bn254::Fq>(&a.to_bytes_le());
+        assert_eq!(bool::from(a.sqrt().is_some()), x.sqrt().is_some());
+
+        let a = Fq2::random(&mut rng);
+        let x = to_ark::<ark_bn254::Fq2>(&a.to_bytes_le());
+        assert_eq!(bool::from(a.sqrt().is_some()), x.sqrt().is_some());
+    }
+}
+
+#[test]
+fn frobenius_test() {
+    let mut rng = OsRng;
+    for _ in 0..FIELD_ITERATIONS {
+        let a = Fq12::random(&mut rng);
+        let mut x = to_ark::<ark_bn254::Fq12>(&a.to_bytes_le());
+        x.frobenius_map_in_place(1);
+
+        assert_eq!(
+            to_ark::<ark_bn254::Fq12>(&a.frobenius_map().to_bytes_le()),
+            x
+        );
+    }
+}
+
+fn check_g1(iterations: usize) {
+    let mut rng = OsRng;
+    let g = G1Affine::generator();
+    assert_eq!(ark_g1(g), ark_bn254::G1Affine::generator());
+
+    for _ in 0..iterations {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = g * a;
+        let q = g * b;
+        let (x, y) = (
+            ark_bn254::G1Affine::generator() * ark_fr(a),
+            ark_bn254::G1Affine::generator() * ark_fr(b),
+        );
+
+        assert_eq!(ark_g1(G1Affine::from(p)), x.into_affine());
+        assert_eq!(ark_g1(G1Affine::from(p + q)), (x + y).into_affine());
+        assert_eq!(ark_g1(G1Affine::from(p.double())), (x + x).into_affine());
+        assert_eq!(ark_g1(G1Affine::from(-p)), (-x).into_affine());
+    }
+    assert_eq!(
+        ark_g1(G1Affine::from(G1Projective::identity())),
+        ark_bn254::G1Affine::identity()
+    );
+}
+
+fn check_g2(iterations: usize) {
+    let mut rng = OsRng;
+    let g = G2Affine::generator();
+    assert_eq!(ark_g2(g), ark_bn254::G2Affine::generator());
+
+    for _ in 0..iterations {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = g * a;
+        let q = g * b;
+        let (x, y) = (
+            ark_bn254::G2Affine::generator() * ark_fr(a),
+            ark_bn254::G2Affine::generator() * ark_fr(b),
+        );
+
+        assert_eq!(ark_g2(G2Affine::from(p)), x.into_affine());
+        assert_eq!(ark_g2(G2Affine::from(p + q)), (x + y).into_affine());
+        assert_eq!(ark_g2(G2Affine::from(p.double())), (x + x).into_affine());
+        assert_eq!(ark_g2(G2Affine::from(-p)), (-x).into_affine());
+    }
+    assert_eq!(
+        ark_g2(G2Affine::from(G2Projective::identity())),
+        ark_bn254::G2Affine::identity()
+    );
+}
+
+/// Miller loop outputs are only defined up to factors killed by the final
+/// exponentiation, so they are compared after arkworks' one.
+fn check_miller_loop(iterations: usize) {
+    let mut rng = OsRng;
+    for n in 1..=iterations {
+        let (g1s, g2s): (Vec<_>, Vec<_>) = (0..n % 4 + 1)
+            .map(|_| {
+                let p = G1Affine::from(G1Affine::generator() * Fr::random(&mut rng));
+                let q = G2Affine::from(G2Affine::generator() * Fr::random(&mut rng));
+                (p, q)
+            })
+            .unzip();
+        let pairs = g1s
+            .iter()
+            .zip(&g2s)
+            .map(|(&p, &q)| (p, G2PairingAffine::from(q)))
+            .collect::<Vec<_>>();
+
+        let f = AteParing::multi_miller_loop(&pairs);
+        let x = to_ark::<ark_bn254::Fq12>(&f.to_bytes_le());
+        let expected = Bn254::multi_pairing(
+            g1s.iter().map(|&p| ark_g1(p)),
+            g2s.iter().map(|&q| ark_g2(q)),
+        );
+
+        assert_eq!(
+            Bn254::final_exponentiation(MillerLoopOutput(x)).unwrap(),
+            expected
+        );
+    }
+}
+
+fn check_final_exp(iterations: usize) {
+    let mut rng = OsRng;
+    for _ in 0..iterations {
+        let f = Fq12::random(&mut rng);
+        let x = to_ark::<ark_bn254::Fq12>(&f.to_bytes_le());
+        let expected = Bn254::final_exponentiation(MillerLoopOutput(x)).unwrap().0;
+
+        assert_eq!(
+            f.final_exp().0.pow(ARK_FINAL_EXP_FACTOR),
+            fq12_from_ark(expected)
+        );
+    }
+}
+
+fn check_pairing(iterations: usize) {
+    let mut rng = OsRng;
+    for _ in 0..iterations {
+        let p = G1Affine::from(G1Affine::generator() * Fr::random(&mut rng));
+        let q = G2Affine::from(G2Affine::generator() * Fr::random(&mut rng));
+        let expected = Bn254::pairing(ark_g1(p), ark_g2(q)).0;
+
+        assert_eq!(
+            AteParing::pairing(p, q).0.pow(ARK_FINAL_EXP_FACTOR),
+            fq12_from_ark(expected)
+        );
+    }
+}
+
+#[test]
+fn g1_test() {
+    check_g1(CURVE_ITERATIONS);
+}
+
+#[test]
+fn g2_test() {
+    check_g2(CURVE_ITERATIONS);
+}
+
+#[test]
+fn miller_loop_test() {
+    check_miller_loop(PAIRING_ITERATIONS);
+}
+
+#[test]
+fn final_exp_test() {
+    check_final_exp(PAIRING_ITERATIONS);
+}
+
+#[test]
+fn pairing_test() {
+    check_pairing(PAIRING_ITERATIONS);
+}
+
+#[test]
+#[ignore]
+fn g1_exhaustive_test() {
+    check_g1(EXHAUSTIVE_CURVE_ITERATIONS);
+}
+
+#[test]
+#[ignore]
+fn g2_exhaustive_test() {
+    check_g2(EXHAUSTIVE_CURVE_ITERATIONS);
+}
+
+#[test]
+#[ignore]
+fn miller_loop_exhaustive_test() {
+    check_miller_loop(EXHAUSTIVE_PAIRING_ITERATIONS);
+}
+
+#[test]
+#[ignore]
+fn final_exp_exhaustive_test() {
+    check_final_exp(EXHAUSTIVE_PAIRING_ITERATIONS);
+}
+
+#[test]
+#[ignore]
+fn pairing_exhaustive_test() {
+    check_pairing(EXHAUSTIVE_PAIRING_ITERATIONS);
+}
@@ -0,0 +1,65 @@
+//! Property tests of the field axioms for every field of the tower.
+use bn254::{Fq, Fq12, Fq2, Fq6};
+use proptest::prelude::*;
+
+/// Elements from arbitrary bytes, with the top three bits of every Fq
+/// coefficient cleared so the encoding is always canonical.
+fn elements<const N: usize, T: core::fmt::Debug>(
+    decode: fn(&[u8; N]) -> T,
+) -> impl Strategy<Value = T> {
+    prop::collection::vec(any::<u8>(), N).prop_map(move |mut bytes| {
+        for chunk in bytes.chunks_mut(32) {
+            chunk[31] &= 0x1f;
+        }
+        decode(&bytes.try_into().unwrap())
+    })
+}
+
+macro_rules! field_axioms {
+    ($name:ident, $field:ident, $len:literal) => {
+        mod $name {
+            use super::*;
+
+            fn element() -> impl Strategy<Value = $field> {
+                elements::<$len, _>(|bytes| $field::from_bytes_le(bytes).unwrap())
+            }
+
+            proptest! {
+                #[test]
+                fn addition(a in element(), b in element(), c in element()) {
+                    prop_assert_eq!(a + b, b + a);
+                    prop_assert_eq!((a + b) + c, a + (b + c));
+                    prop_assert_eq!(a + $field::zero(), a);
+                    prop_assert_eq!(a + -a, $field::zero());
+                    prop_assert_eq!(a - b, a + -b);
+                }
+
+                #[test]
+                fn multiplication(a in element(), b in element(), c in element()) {
+                    prop_assert_eq!(a * b, b * a);
+                    prop_assert_eq!((a * b) * c, a * (b * c));
+                    prop_assert_eq!(a * $field::one(), a);
+                    prop_assert_eq!(a * $field::zero(), $field::zero());
+                    prop_assert_eq!(a.square(), a * a);
+                }
+
+                #[test]
+                fn distributivity(a in element(), b in element(), c in element()) {
+                    prop_assert_eq!(a * (b + c), a * b + a * c);
+                    prop_assert_eq!((a - b) * c, a * c - b * c);
+                }
+
+                #[test]
+                fn inverse(a in element()) {
+                    prop_assume!(a != $field::zero());
+                    prop_assert_eq!(a * a.invert().unwrap(), $field::one());
+                }
+            }
+        }
+    };
+}
+
+field_axioms!(fq, Fq, 32);
+field_axioms!(fq2, Fq2, 64);
+field_axioms!(fq6, Fq6, 192);
+field_axioms!(fq12, Fq12, 384);
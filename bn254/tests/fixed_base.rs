@@ -0,0 +1,101 @@
+use bn254::{FixedBaseTable, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use rand_core::OsRng;
+
+/// Scalars whose signed 5-bit digits hit the edges of the recoding: every
+/// window at 15 or 16, carries running through every window, and the top
+/// of `[0, r)`.
+fn edge_scalars() -> Vec<Fr> {
+    let mut scalars = vec![Fr::zero(), Fr::one(), -Fr::one(), -Fr::from_u64(2)];
+    scalars.extend([15, 16, 17, 31, 32, 33, 0x210, 0x1ef, u64::MAX].map(Fr::from_u64));
+    for window in ["01111", "10000", "11111"] {
+        let bits = window.repeat(50);
+        scalars.push(Fr::from_str_radix(&bits, 2).unwrap());
+    }
+    scalars
+}
+
+#[test]
+fn g1_fixed_base_test() {
+    let mut rng = OsRng;
+    let g = G1Affine::generator();
+    let table = FixedBaseTable::from(g);
+
+    for k in edge_scalars() {
+        assert_eq!(&table * k, g * k, "{k:?}");
+    }
+    for _ in 0..20 {
+        let k = Fr::random(&mut rng);
+        assert_eq!(&table * k, g * k);
+    }
+
+    let p = G1Affine::from(g * Fr::random(&mut rng));
+    let table = FixedBaseTable::from(p);
+    let k = Fr::random(&mut rng);
+    assert_eq!(&table * k, p * k);
+
+    let table = FixedBaseTable::from(G1Affine::identity());
+    assert!((&table * k).is_identity());
+}
+
+#[test]
+fn g2_fixed_base_test() {
+    let mut rng = OsRng;
+    let g = G2Affine::generator();
+    let table = FixedBaseTable::from(g);
+
+    for k in edge_scalars() {
+        assert_eq!(&table * k, g * k, "{k:?}");
+    }
+    for _ in 0..20 {
+        let k = Fr::random(&mut rng);
+        assert_eq!(&table * k, g * k);
+    }
+
+    let table = FixedBaseTable::from(G2Affine::identity());
+    assert!((&table * Fr::random(&mut rng)).is_identity());
+}
+
+#[test]
+fn mixed_addition_test() {
+    let mut rng = OsRng;
+    let p = G1Projective::generator() * Fr::random(&mut rng);
+    let q = G1Affine::from(G1Projective::generator() * Fr::random(&mut rng));
+
+    assert_eq!(p + q, p + G1Projective::from(q));
+    assert_eq!(p + G1Affine::from(p), p.double());
+    assert!((p + G1Affine::from(-p)).is_identity());
+    assert_eq!(p + G1Affine::identity(), p);
+    assert_eq!(G1Projective::identity() + q, G1Projective::from(q));
+
+    let p = G2Projective::generator() * Fr::random(&mut rng);
+    let q = G2Affine::from(G2Projective::generator() * Fr::random(&mut rng));
+
+    assert_eq!(p + q, p + G2Projective::from(q));
+    assert_eq!(p + G2Affine::from(p), p.double());
+    assert!((p + G2Affine::from(-p)).is_identity());
+    assert_eq!(p + G2Affine::identity(), p);
+    assert_eq!(G2Projective::identity() + q, G2Projective::from(q));
+}
+
+#[test]
+fn fixed_base_debug_test() {
+    let table = FixedBaseTable::from(G2Affine::generator());
+    let debug = format!("{table:?}");
+    assert!(debug.starts_with("FixedBaseTable { base: "), "{debug}");
+    assert!(debug.ends_with("windows: 52, multiples: 16 }"), "{debug}");
+    assert!(debug.len() < 1024);
+}
+
+#[test]
+fn fixed_base_small_stack_test() {
+    // the G2 table alone is larger than this stack
+    let g = G2Affine::generator();
+    let k = Fr::from_u64(0x1234);
+    let res = std::thread::Builder::new()
+        .stack_size(96 * 1024)
+        .spawn(move || &FixedBaseTable::from(g) * k)
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(res, g * k);
+}
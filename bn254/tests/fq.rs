@@ -0,0 +1,216 @@
+use bn254::{Fq, Fq12, Fq2, Fq6, Gt};
+use rand_core::OsRng;
+
+/// q - 2, the Fermat inversion exponent
+const Q_MINUS_TWO: [u64; 4] = [
+    0x3c208c16d87cfd45,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+#[test]
+fn fq_invert_test() {
+    let mut rng = OsRng;
+    assert!(bool::from(Fq::zero().invert().is_none()));
+    assert_eq!(Fq::one().invert().unwrap(), Fq::one());
+
+    for _ in 0..100 {
+        let a = Fq::random(&mut rng);
+        let inv = a.invert().unwrap();
+
+        assert_eq!(a * inv, Fq::one());
+        assert_eq!(inv, a.pow(Q_MINUS_TWO));
+    }
+}
+
+#[test]
+fn fq_batch_invert_test() {
+    let mut rng = OsRng;
+    let mut elems = (0..20).map(|_| Fq::random(&mut rng)).collect::<Vec<_>>();
+    elems[0] = Fq::zero();
+    elems[7] = Fq::zero();
+    let expected = elems
+        .iter()
+        .map(|a| a.invert().unwrap_or(Fq::zero()))
+        .collect::<Vec<_>>();
+
+    Fq::batch_invert(&mut elems);
+    assert_eq!(elems, expected);
+
+    // the scratch space may be longer than the elements
+    let mut scratch = [Fq::one(); 24];
+    Fq::batch_invert_with_scratch(&mut elems, &mut scratch);
+    let expected = expected
+        .iter()
+        .map(|a| a.invert().unwrap_or(Fq::zero()))
+        .collect::<Vec<_>>();
+    assert_eq!(elems, expected);
+}
+
+#[test]
+fn fq_sqrt_test() {
+    let mut rng = OsRng;
+    assert_eq!(Fq::zero().sqrt().unwrap(), Fq::zero());
+    assert_eq!(Fq::zero().legendre(), 0);
+    // -1 is a non-residue since q = 3 mod 4
+    assert_eq!((-Fq::one()).legendre(), -1);
+
+    let mut non_squares = 0;
+    for _ in 0..100 {
+        let a = Fq::random(&mut rng);
+        let square = a.square();
+        let root = square.sqrt().unwrap();
+
+        assert!(root == a || root == -a);
+        assert_eq!(square.legendre(), 1);
+        assert!(bool::from(square.is_square()));
+
+        if a.legendre() == -1 {
+            non_squares += 1;
+            assert!(bool::from(a.sqrt().is_none()));
+            assert!(!bool::from(a.is_square()));
+        }
+    }
+    assert!(non_squares > 0);
+}
+
+#[test]
+fn fq2_sqrt_test() {
+    let mut rng = OsRng;
+    assert_eq!(Fq2::zero().sqrt().unwrap(), Fq2::zero());
+    // every element of Fq is a square in Fq2
+    assert!(bool::from((-Fq2::one()).is_square()));
+
+    let mut non_squares = 0;
+    for _ in 0..100 {
+        let a = Fq2::random(&mut rng);
+        let square = a.square();
+        let root = square.sqrt().unwrap();
+
+        assert!(root == a || root == -a);
+        assert_eq!(square.legendre(), 1);
+
+        if a.legendre() == -1 {
+            non_squares += 1;
+            assert!(bool::from(a.sqrt().is_none()));
+            assert!(!bool::from(a.is_square()));
+        }
+    }
+    assert!(non_squares > 0);
+}
+
+const MODULUS_DEC: &str =
+    "21888242871839275222246405745257275088696311157297823662689037894645226208583";
+const MODULUS_MINUS_ONE_DEC: &str =
+    "21888242871839275222246405745257275088696311157297823662689037894645226208582";
+const MODULUS_BE: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+#[test]
+fn fq_encoding_test() {
+    let mut rng = OsRng;
+    let mut one = [0; 32];
+    one[31] = 1;
+    assert_eq!(Fq::one().to_bytes_be(), one);
+    assert_eq!(Fq::from_u64(1), Fq::one());
+    assert_eq!(Fq::from_str_radix("ff", 16), Some(Fq::from_u64(255)));
+    assert_eq!(
+        Fq::from_str_radix(MODULUS_MINUS_ONE_DEC, 10),
+        Some(-Fq::one())
+    );
+    assert_eq!(Fq::from_str_radix(MODULUS_DEC, 10), None);
+    assert_eq!(Fq::from_str_radix("", 10), None);
+    assert_eq!(Fq::from_str_radix("12z", 10), None);
+    assert!(bool::from(Fq::from_bytes_be(&MODULUS_BE).is_none()));
+    assert!(bool::from(Fq::from_bytes_be(&[0xff; 32]).is_none()));
+    assert_eq!(
+        Fq::from_u64(255).to_string(),
+        "0x00000000000000000000000000000000000000000000000000000000000000ff"
+    );
+    assert_eq!(
+        format!("{:x}", Fq::from_u64(16)).trim_start_matches('0'),
+        "10"
+    );
+
+    for _ in 0..100 {
+        let a = Fq::random(&mut rng);
+        assert_eq!(Fq::from_bytes_le(&a.to_bytes_le()).unwrap(), a);
+        assert_eq!(Fq::from_bytes_be(&a.to_bytes_be()).unwrap(), a);
+        assert_eq!(Fq::from_str_radix(&format!("{:x}", a), 16), Some(a));
+    }
+}
+
+#[test]
+fn fq2_encoding_test() {
+    let mut rng = OsRng;
+    let a = Fq2::new(Fq::from_u64(1), Fq::from_u64(2));
+    let bytes = a.to_bytes_be();
+
+    // c1 comes first in the big-endian encoding
+    assert_eq!(bytes[31], 2);
+    assert_eq!(bytes[63], 1);
+    assert_eq!(
+        a.to_string(),
+        format!("{} + {} * u", Fq::from_u64(1), Fq::from_u64(2))
+    );
+    assert_eq!(Fq2::from_u64(5), Fq2::new(Fq::from_u64(5), Fq::zero()));
+    assert_eq!(Fq2::from_str_radix("ff", 16), Some(Fq2::from_u64(255)));
+    assert_eq!(Fq2::from_str_radix(MODULUS_DEC, 10), None);
+    assert_eq!(Fq2::from_str_radix("1 + 2 * u", 10), None);
+
+    for _ in 0..100 {
+        let a = Fq2::random(&mut rng);
+        assert_eq!(Fq2::from_bytes_le(&a.to_bytes_le()).unwrap(), a);
+        assert_eq!(Fq2::from_bytes_be(&a.to_bytes_be()).unwrap(), a);
+    }
+}
+
+#[test]
+fn fq6_encoding_test() {
+    let mut rng = OsRng;
+    let a = Fq6::from_u64(1);
+    let bytes = a.to_bytes_be();
+
+    // c2 comes first in the big-endian encoding, and c0.c0 last
+    assert_eq!(bytes[191], 1);
+    assert_eq!(bytes[..191], [0; 191]);
+    assert_eq!(Fq6::from_bytes_be(&bytes).unwrap(), a);
+    assert!(bool::from(Fq6::from_bytes_be(&[0xff; 192]).is_none()));
+    assert_eq!(format!("{:x}", a), hex::encode(bytes));
+    assert_eq!(format!("{:#x}", a), format!("0x{}", hex::encode(bytes)));
+
+    for _ in 0..100 {
+        let a = Fq6::random(&mut rng);
+        assert_eq!(Fq6::from_bytes_le(&a.to_bytes_le()).unwrap(), a);
+        assert_eq!(Fq6::from_bytes_be(&a.to_bytes_be()).unwrap(), a);
+    }
+}
+
+#[test]
+fn fq12_encoding_test() {
+    let f = Gt::generator().0;
+
+    assert_eq!(Fq12::from_bytes_le(&f.to_bytes_le()).unwrap(), f);
+    assert_eq!(Fq12::from_bytes_be(&f.to_bytes_be()).unwrap(), f);
+    assert!(bool::from(Fq12::from_bytes_le(&[0xff; 384]).is_none()));
+
+    let five = Fq12::from_u64(5);
+    assert_eq!(five, Fq12::from_u64(2) + Fq12::from_u64(3));
+    assert_eq!(five * Fq12::from_u64(5).invert().unwrap(), Fq12::one());
+    assert_eq!(Fq12::from_str_radix("5", 10), Some(five));
+    assert_eq!(Fq12::from_str_radix("101", 2), Some(five));
+    assert_eq!(Fq6::from_str_radix("5", 10), Some(Fq6::from_u64(5)));
+    assert_eq!(Fq12::from_str_radix("", 10), None);
+    assert_eq!(Fq12::from_str_radix("-5", 10), None);
+}
+
+#[test]
+fn fq12_invert_test() {
+    let f = Gt::generator().0;
+
+    assert_eq!(f * f.invert().unwrap(), Fq12::one());
+    assert!(bool::from(Fq12::default().invert().is_none()));
+}
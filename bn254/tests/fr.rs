@@ -0,0 +1,94 @@
+use bn254::Fr;
+use rand_core::OsRng;
+
+/// r - 2, the Fermat inversion exponent
+const R_MINUS_TWO: [u64; 4] = [
+    0x43e1f593efffffff,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+#[test]
+fn invert_test() {
+    let mut rng = OsRng;
+    assert!(bool::from(Fr::zero().invert().is_none()));
+    assert_eq!(Fr::one().invert().unwrap(), Fr::one());
+
+    for _ in 0..100 {
+        let a = Fr::random(&mut rng);
+        let inv = a.invert().unwrap();
+
+        assert_eq!(a * inv, Fr::one());
+        assert_eq!(inv, a.pow(R_MINUS_TWO));
+    }
+}
+
+#[test]
+fn batch_invert_test() {
+    let mut rng = OsRng;
+    let mut elems = (0..20).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+    elems[0] = Fr::zero();
+    elems[7] = Fr::zero();
+    let expected = elems
+        .iter()
+        .map(|a| a.invert().unwrap_or(Fr::zero()))
+        .collect::<Vec<_>>();
+
+    Fr::batch_invert(&mut elems);
+
+    assert_eq!(elems, expected);
+}
+
+#[test]
+fn sqrt_test() {
+    let mut rng = OsRng;
+    assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+    assert_eq!(Fr::zero().legendre(), 0);
+
+    let mut non_squares = 0;
+    for _ in 0..100 {
+        let a = Fr::random(&mut rng);
+        let square = a.square();
+        let root = square.sqrt().unwrap();
+
+        assert!(root == a || root == -a);
+        assert_eq!(square.legendre(), 1);
+        assert!(bool::from(square.is_square()));
+
+        if a.legendre() == -1 {
+            non_squares += 1;
+            assert!(bool::from(a.sqrt().is_none()));
+            assert!(!bool::from(a.is_square()));
+        }
+    }
+    assert!(non_squares > 0);
+}
+
+#[test]
+fn encoding_test() {
+    let mut rng = OsRng;
+    let modulus_dec =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+    let modulus_minus_one_dec =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+
+    assert_eq!(
+        Fr::from_str_radix(modulus_minus_one_dec, 10),
+        Some(-Fr::one())
+    );
+    assert_eq!(Fr::from_str_radix(modulus_dec, 10), None);
+    assert_eq!(Fr::from_u64(7).to_bytes_le()[0], 7);
+    assert!(bool::from(Fr::from_bytes_le(&[0xff; 32]).is_none()));
+    assert_eq!(
+        Fr::one().to_string(),
+        "0x0000000000000000000000000000000000000000000000000000000000000001"
+    );
+
+    for _ in 0..100 {
+        let a = Fr::random(&mut rng);
+        assert_eq!(Fr::from_bytes_le(&a.to_bytes_le()).unwrap(), a);
+        assert_eq!(Fr::from_bytes_be(&a.to_bytes_be()).unwrap(), a);
+        assert_eq!(Fr::from_str_radix(&format!("{}", a)[2..], 16), Some(a));
+    }
+}
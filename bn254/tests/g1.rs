@@ -0,0 +1,146 @@
+use bn254::{Fq, Fr, G1Affine, G1Projective};
+use rand_core::OsRng;
+
+/// Plain double-and-add over the big-endian scalar bytes.
+fn reference_mul(p: G1Projective, k: Fr) -> G1Projective {
+    let mut res = G1Projective::identity();
+    for byte in k.to_bytes_be() {
+        for i in (0..8).rev() {
+            res = res.double();
+            if (byte >> i) & 1 == 1 {
+                res += p;
+            }
+        }
+    }
+    res
+}
+
+#[test]
+fn g1_scalar_mul_test() {
+    let mut rng = OsRng;
+    let g = G1Projective::generator();
+    // λ = q^2 mod r, the eigenvalue of the GLV endomorphism
+    let lambda = Fr::from_str_radix(
+        "30644e72e131a029048b6e193fd84104cc37a73fec2bc5e9b8ca0b2d36636f24",
+        16,
+    )
+    .unwrap();
+
+    assert!((g * Fr::zero()).is_identity());
+    assert!((G1Projective::identity() * Fr::random(&mut rng)).is_identity());
+    assert_eq!(g * Fr::one(), g);
+    assert_eq!(g * -Fr::one(), -g);
+    assert_eq!(g * Fr::from_u64(2), g.double());
+    assert_eq!(g * Fr::from_u64(3), g + g + g);
+
+    let mut edges = vec![lambda, lambda + Fr::one(), lambda - Fr::one(), -lambda];
+    edges.extend((0..64).map(Fr::from_u64));
+    edges.push(Fr::from_u64(u64::MAX));
+    for k in edges {
+        assert_eq!(g * k, reference_mul(g, k), "{k:?}");
+        assert_eq!(g * -k, -reference_mul(g, k), "{k:?}");
+    }
+
+    for _ in 0..20 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = g * Fr::random(&mut rng);
+
+        assert_eq!(p * a, reference_mul(p, a));
+        assert_eq!(p * a + p * b, p * (a + b));
+        assert_eq!((p * a) * b, p * (a * b));
+        assert_eq!(G1Affine::from(p) * a, p * a);
+    }
+}
+
+#[test]
+fn g1_batch_normalize_test() {
+    let mut rng = OsRng;
+    let mut points = (0..20)
+        .map(|_| G1Projective::generator() * Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+    points[0] = G1Projective::identity();
+    points[9] = G1Projective::identity();
+
+    let mut affine = vec![G1Affine::identity(); points.len()];
+    G1Projective::batch_normalize(&points, &mut affine);
+
+    for (p, q) in points.iter().zip(&affine) {
+        assert_eq!(G1Affine::from(*p), *q);
+    }
+    G1Projective::batch_normalize(&[], &mut []);
+}
+
+#[test]
+fn g1_batch_add_test() {
+    let mut rng = OsRng;
+    let g = G1Affine::generator();
+    let p = G1Affine::from(g * Fr::random(&mut rng));
+    let q = G1Affine::from(g * Fr::random(&mut rng));
+    let i = G1Affine::identity();
+
+    // generic, doubling, opposite and identity pairs in one batch
+    let mut lhs = [p, p, p, i, p, i];
+    let rhs = [q, p, -p, q, i, i];
+    let mut scratch = [Fq::zero(); 6];
+    G1Affine::batch_add_with_scratch(&mut lhs, &rhs, &mut scratch);
+    for ((sum, a), b) in lhs.iter().zip([p, p, p, i, p, i]).zip(rhs) {
+        assert_eq!(G1Projective::from(*sum), G1Projective::from(a) + b);
+    }
+
+    for n in [0, 1, 2, 3, 7, 64, 101] {
+        let points = (0..n)
+            .map(|_| G1Affine::from(g * Fr::random(&mut rng)))
+            .collect::<Vec<_>>();
+        let expected = points
+            .iter()
+            .fold(G1Projective::identity(), |acc, p| acc + *p);
+        assert_eq!(G1Projective::from(G1Affine::sum(&points)), expected);
+    }
+    assert_eq!(G1Affine::sum(&[p, -p, q]), q);
+}
+
+#[test]
+fn g1_compressed_encoding_test() {
+    let mut rng = OsRng;
+    let g = G1Affine::generator();
+
+    // (1, 2), with 2 the smaller of the two roots
+    let mut expected = [0; 32];
+    expected[0] = 0x80;
+    expected[31] = 1;
+    assert_eq!(g.to_compressed(), expected);
+    expected[0] = 0xc0;
+    assert_eq!((-g).to_compressed(), expected);
+
+    let mut identity = [0; 32];
+    identity[0] = 0x40;
+    assert_eq!(G1Affine::identity().to_compressed(), identity);
+    assert!(G1Affine::from_compressed(&identity).unwrap().is_identity());
+
+    for _ in 0..100 {
+        let p = G1Affine::from(g * Fr::random(&mut rng));
+        let bytes = p.to_compressed();
+        assert_eq!(G1Affine::from_compressed(&bytes).unwrap(), p);
+        assert_eq!(bytes[1..], p.x().to_bytes_be()[1..]);
+    }
+
+    // no flags, unknown flags on the identity, a non-canonical x and an x
+    // with no point on the curve
+    let mut bytes = g.to_compressed();
+    bytes[0] &= 0x3f;
+    assert!(bool::from(G1Affine::from_compressed(&bytes).is_none()));
+    identity[31] = 1;
+    assert!(bool::from(G1Affine::from_compressed(&identity).is_none()));
+    let mut bytes = (-Fq::one()).to_bytes_be();
+    bytes[31] += 1;
+    bytes[0] |= 0x80;
+    assert!(bool::from(G1Affine::from_compressed(&bytes).is_none()));
+    let x = (1..)
+        .map(Fq::from_u64)
+        .find(|x| !bool::from((x.square() * *x + Fq::from_u64(3)).is_square()))
+        .unwrap();
+    let mut bytes = x.to_bytes_be();
+    bytes[0] |= 0x80;
+    assert!(bool::from(G1Affine::from_compressed(&bytes).is_none()));
+}
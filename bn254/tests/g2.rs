@@ -0,0 +1,162 @@
+use bn254::{AteParing, Fq2, Fr, G1Affine, G2Affine, G2Projective};
+use rand_core::OsRng;
+
+/// Plain double-and-add over the big-endian scalar bytes.
+fn reference_mul(p: G2Projective, k: Fr) -> G2Projective {
+    let mut res = G2Projective::identity();
+    for byte in k.to_bytes_be() {
+        for i in (0..8).rev() {
+            res = res.double();
+            if (byte >> i) & 1 == 1 {
+                res += p;
+            }
+        }
+    }
+    res
+}
+
+/// μ = q mod r = 6x^2, the eigenvalue of ψ on G2
+fn mu() -> Fr {
+    Fr::from_str_radix("6f4d8248eeb859fbf83e9682e87cfd46", 16).unwrap()
+}
+
+#[test]
+fn g2_psi_test() {
+    let mut rng = OsRng;
+    let g = G2Affine::generator();
+
+    assert!(G2Affine::identity().psi().is_identity());
+    assert_eq!(G2Projective::from(g.psi()), g * mu());
+
+    for _ in 0..5 {
+        let p = G2Affine::from(g * Fr::random(&mut rng));
+        let psi = p.psi();
+
+        assert!(psi.is_on_curve());
+        assert_eq!(G2Projective::from(psi), p * mu());
+        // ψ commutes with the pairing up to the matching power of Gt
+        assert_eq!(
+            AteParing::pairing(G1Affine::generator(), psi),
+            AteParing::pairing(G1Affine::generator(), p) * mu()
+        );
+    }
+}
+
+#[test]
+fn g2_scalar_mul_test() {
+    let mut rng = OsRng;
+    let g = G2Projective::generator();
+
+    assert!((g * Fr::zero()).is_identity());
+    assert!((G2Projective::identity() * Fr::random(&mut rng)).is_identity());
+    assert_eq!(g * Fr::one(), g);
+    assert_eq!(g * -Fr::one(), -g);
+    assert_eq!(g * Fr::from_u64(2), g.double());
+    assert_eq!(g * Fr::from_u64(3), g + g + g);
+
+    let mu = mu();
+    let mut edges = vec![mu, mu * mu, mu * mu * mu, mu + Fr::one(), -mu];
+    edges.extend((0..64).map(Fr::from_u64));
+    edges.push(Fr::from_u64(u64::MAX));
+    for k in edges {
+        assert_eq!(g * k, reference_mul(g, k), "{k:?}");
+        assert_eq!(g * -k, -reference_mul(g, k), "{k:?}");
+    }
+
+    for _ in 0..10 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let p = g * Fr::random(&mut rng);
+
+        assert_eq!(p * a, reference_mul(p, a));
+        assert_eq!(p * a + p * b, p * (a + b));
+        assert_eq!((p * a) * b, p * (a * b));
+        assert_eq!(G2Affine::from(p) * a, p * a);
+    }
+}
+
+#[test]
+fn g2_batch_normalize_test() {
+    let mut rng = OsRng;
+    let mut points = (0..20)
+        .map(|_| G2Projective::generator() * Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+    points[0] = G2Projective::identity();
+    points[9] = G2Projective::identity();
+
+    let mut affine = vec![G2Affine::identity(); points.len()];
+    G2Projective::batch_normalize(&points, &mut affine);
+
+    for (p, q) in points.iter().zip(&affine) {
+        assert_eq!(G2Affine::from(*p), *q);
+    }
+    G2Projective::batch_normalize(&[], &mut []);
+}
+
+#[test]
+fn g2_batch_add_test() {
+    let mut rng = OsRng;
+    let g = G2Affine::generator();
+    let p = G2Affine::from(g * Fr::random(&mut rng));
+    let q = G2Affine::from(g * Fr::random(&mut rng));
+    let i = G2Affine::identity();
+
+    // generic, doubling, opposite and identity pairs in one batch
+    let mut lhs = [p, p, p, i, p, i];
+    let rhs = [q, p, -p, q, i, i];
+    let mut scratch = [Fq2::zero(); 6];
+    G2Affine::batch_add_with_scratch(&mut lhs, &rhs, &mut scratch);
+    for ((sum, a), b) in lhs.iter().zip([p, p, p, i, p, i]).zip(rhs) {
+        assert_eq!(G2Projective::from(*sum), G2Projective::from(a) + b);
+    }
+
+    for n in [0, 1, 2, 3, 7, 64, 101] {
+        let points = (0..n)
+            .map(|_| G2Affine::from(g * Fr::random(&mut rng)))
+            .collect::<Vec<_>>();
+        let expected = points
+            .iter()
+            .fold(G2Projective::identity(), |acc, p| acc + *p);
+        assert_eq!(G2Projective::from(G2Affine::sum(&points)), expected);
+    }
+    assert_eq!(G2Affine::sum(&[p, -p, q]), q);
+}
+
+#[test]
+fn g2_compressed_encoding_test() {
+    let mut rng = OsRng;
+    let g = G2Affine::generator();
+
+    let mut identity = [0; 64];
+    identity[0] = 0x40;
+    assert_eq!(G2Affine::identity().to_compressed(), identity);
+    assert!(G2Affine::from_compressed(&identity).unwrap().is_identity());
+    assert_eq!(
+        g.to_compressed()[0] ^ (-g).to_compressed()[0],
+        0x40,
+        "negation only flips the sign flag"
+    );
+
+    for _ in 0..20 {
+        let p = G2Affine::from(g * Fr::random(&mut rng));
+        let bytes = p.to_compressed();
+        assert_eq!(G2Affine::from_compressed(&bytes).unwrap(), p);
+        assert_eq!(bytes[1..], p.x().to_bytes_be()[1..]);
+    }
+
+    let mut bytes = g.to_compressed();
+    bytes[0] &= 0x3f;
+    assert!(bool::from(G2Affine::from_compressed(&bytes).is_none()));
+    identity[63] = 1;
+    assert!(bool::from(G2Affine::from_compressed(&identity).is_none()));
+
+    // a point of the twist outside the order r subgroup
+    let b = g.y().square() - g.x().square() * g.x();
+    let x = (1..)
+        .map(Fq2::from_u64)
+        .find(|x| bool::from((x.square() * *x + b).is_square()))
+        .unwrap();
+    let mut bytes = x.to_bytes_be();
+    bytes[0] |= 0x80;
+    assert!(bool::from(G2Affine::from_compressed(&bytes).is_none()));
+}
@@ -0,0 +1,111 @@
+use bn254::AteParing;
+use bn254::{Fq, Fr, G1Affine, G2Affine, Gt};
+use rand_core::OsRng;
+
+#[test]
+fn gt_encoding_test() {
+    let mut rng = OsRng;
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+
+    for _ in 0..5 {
+        let a = G1Affine::from(g1 * Fr::random(&mut rng));
+        let gt = AteParing::pairing(a, g2);
+
+        assert_eq!(Gt::from_bytes(&gt.to_bytes()).unwrap(), gt);
+        assert_eq!(Gt::from_compressed(&gt.to_compressed()).unwrap(), gt);
+    }
+
+    let identity = Gt::identity();
+    assert_eq!(identity.to_compressed(), [0; 192]);
+    assert_eq!(Gt::from_bytes(&identity.to_bytes()).unwrap(), identity);
+    assert_eq!(
+        Gt::from_compressed(&identity.to_compressed()).unwrap(),
+        identity
+    );
+}
+
+#[test]
+fn gt_encoding_rejects_non_members_test() {
+    // -1 has norm one but order two
+    let mut minus_one = [0; 384];
+    minus_one[352..].copy_from_slice(&(-Fq::one()).to_bytes_be());
+    assert!(bool::from(Gt::from_bytes(&minus_one).is_none()));
+
+    // 2 lies in Fq, outside the cyclotomic subgroup
+    let mut two = [0; 384];
+    two[383] = 2;
+    assert!(bool::from(Gt::from_bytes(&two).is_none()));
+
+    // g = 1 decodes to an element of norm one which is not of order r
+    let mut one = [0; 192];
+    one[191] = 1;
+    assert!(bool::from(Gt::from_compressed(&one).is_none()));
+    assert!(bool::from(Gt::from_compressed(&[0xff; 192]).is_none()));
+}
+
+#[test]
+fn gt_scalar_mul_test() {
+    let mut rng = OsRng;
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let gt = Gt::generator();
+
+    assert_eq!(gt * Fr::zero(), Gt::identity());
+    assert_eq!(gt * Fr::one(), gt);
+    assert_eq!(gt * -Fr::one(), -gt);
+    assert_eq!(gt * Fr::from_u64(3), gt + gt + gt);
+
+    for _ in 0..5 {
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+
+        assert_eq!(gt * a, AteParing::pairing(G1Affine::from(g1 * a), g2));
+        assert_eq!(gt * a + gt * b, gt * (a + b));
+        assert_eq!(gt * a - gt * b, gt * (a - b));
+        assert_eq!((gt * a) * b, gt * (a * b));
+
+        let bytes = a.to_bytes_le();
+        let limbs =
+            [0, 1, 2, 3].map(|i| u64::from_le_bytes(bytes[i * 8..][..8].try_into().unwrap()));
+        assert_eq!(gt.0.pow(limbs), (gt * a).0);
+    }
+}
+
+#[test]
+fn gt_group_test() {
+    let mut rng = OsRng;
+    let a = Gt::random(&mut rng);
+    let b = Gt::random(&mut rng);
+
+    assert!(Gt::identity().is_identity());
+    assert!(!a.is_identity());
+    assert!((a - a).is_identity());
+
+    let mut c = a;
+    c += b;
+    assert_eq!(c, a + b);
+    c -= b;
+    assert_eq!(c, a);
+
+    assert!(bool::from(a.is_in_subgroup()));
+    assert!(bool::from(Gt::identity().is_in_subgroup()));
+}
+
+#[test]
+fn gt_hash_to_bytes_test() {
+    let gt = Gt::generator();
+    let mut short = [0; 32];
+    let mut long = [0; 80];
+    gt.hash_to_bytes(b"info", &mut short);
+    gt.hash_to_bytes(b"info", &mut long);
+
+    assert_eq!(short, long[..32]);
+    assert_ne!(long[..32], long[32..64]);
+
+    let mut other = [0; 32];
+    gt.hash_to_bytes(b"other", &mut other);
+    assert_ne!(short, other);
+    (gt + gt).hash_to_bytes(b"info", &mut other);
+    assert_ne!(short, other);
+}
@@ -0,0 +1,143 @@
+//! Known-answer tests from go-ethereum's precompile test data.
+//!
+//! `bn256Add.json`, `bn256ScalarMul.json` and `bn256Pairing.json` are
+//! go-ethereum's vectors for the EIP-196 and EIP-197 precompiles, so passing
+//! them means we agree with the EVM. `bn256PairingInvalid.json` holds inputs
+//! the pairing precompile must reject: malformed lengths, non-canonical
+//! coordinates, points off the curve and a twist point outside G2.
+use bn254::{AteParing, Fr, G1Affine, G1Projective, G2Affine, G2PairingAffine, Gt};
+use serde_json::Value;
+
+/// Name, input and expected output or error of a test vector.
+type Vector = (String, Vec<u8>, Result<Vec<u8>, String>);
+
+fn vectors(json: &str) -> Vec<Vector> {
+    let vectors: Vec<Value> = serde_json::from_str(json).unwrap();
+    vectors
+        .into_iter()
+        .map(|v| {
+            let name = v["Name"].as_str().unwrap().to_owned();
+            let input = hex::decode(v["Input"].as_str().unwrap()).unwrap();
+            let expected = match v["Expected"].as_str() {
+                Some(expected) => Ok(hex::decode(expected).unwrap()),
+                None => Err(v["ExpectedError"].as_str().unwrap().to_owned()),
+            };
+            (name, input, expected)
+        })
+        .collect()
+}
+
+/// The precompiles read missing input bytes as zeros.
+fn padded<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut bytes = [0; N];
+    let len = input.len().min(N);
+    bytes[..len].copy_from_slice(&input[..len]);
+    bytes
+}
+
+fn g1(bytes: &[u8]) -> Result<G1Affine, &'static str> {
+    Option::from(G1Affine::from_uncompressed(bytes.try_into().unwrap())).ok_or("invalid g1 point")
+}
+
+fn g2(bytes: &[u8]) -> Result<G2Affine, &'static str> {
+    Option::from(G2Affine::from_uncompressed(bytes.try_into().unwrap())).ok_or("invalid g2 point")
+}
+
+/// The EVM multiplies by the full 256-bit scalar, which is the same as
+/// multiplying by its reduction mod r.
+fn scalar(bytes: &[u8]) -> Fr {
+    let base = Fr::from_u64(1 << 32).square();
+    bytes.chunks(8).fold(Fr::zero(), |acc, chunk| {
+        acc * base + Fr::from_u64(u64::from_be_bytes(chunk.try_into().unwrap()))
+    })
+}
+
+/// The EIP-197 pairing check, evaluated both through `multi_pairing` and
+/// through `multi_miller_loop` followed by `final_exp`.
+fn pairing_check(input: &[u8]) -> Result<bool, &'static str> {
+    if !input.len().is_multiple_of(192) {
+        return Err("invalid input length");
+    }
+    let pairs = input
+        .chunks(192)
+        .map(|pair| Ok((g1(&pair[..64])?, g2(&pair[64..])?)))
+        .collect::<Result<Vec<_>, &'static str>>()?;
+    let prepared = pairs
+        .iter()
+        .map(|&(p, q)| (p, G2PairingAffine::from(q)))
+        .collect::<Vec<_>>();
+
+    let by_pairing = AteParing::multi_pairing(&pairs);
+    let by_miller_loop = AteParing::multi_miller_loop(&prepared).final_exp();
+    assert_eq!(by_pairing, by_miller_loop);
+
+    Ok(by_pairing == Gt::identity())
+}
+
+#[test]
+fn bn256_add_test() {
+    for (name, input, expected) in vectors(include_str!("fixtures/bn256Add.json")) {
+        let input = padded::<128>(&input);
+        let sum = G1Projective::from(g1(&input[..64]).unwrap())
+            + G1Projective::from(g1(&input[64..]).unwrap());
+
+        assert_eq!(
+            G1Affine::from(sum).to_uncompressed().to_vec(),
+            expected.unwrap(),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn bn256_scalar_mul_test() {
+    for (name, input, expected) in vectors(include_str!("fixtures/bn256ScalarMul.json")) {
+        let input = padded::<96>(&input);
+        let product = g1(&input[..64]).unwrap() * scalar(&input[64..]);
+
+        assert_eq!(
+            G1Affine::from(product).to_uncompressed().to_vec(),
+            expected.unwrap(),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn bn256_pairing_test() {
+    for (name, input, expected) in vectors(include_str!("fixtures/bn256Pairing.json")) {
+        let mut output = [0; 32];
+        output[31] = pairing_check(&input).unwrap() as u8;
+
+        assert_eq!(output.to_vec(), expected.unwrap(), "{name}");
+    }
+}
+
+#[test]
+fn bn256_pairing_invalid_test() {
+    for (name, input, expected) in vectors(include_str!("fixtures/bn256PairingInvalid.json")) {
+        assert!(expected.is_err(), "{name}");
+        assert!(pairing_check(&input).is_err(), "{name}");
+    }
+}
+
+#[test]
+fn uncompressed_encoding_test() {
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+
+    assert_eq!(
+        G1Affine::from_uncompressed(&g1.to_uncompressed()).unwrap(),
+        g1
+    );
+    assert_eq!(
+        G2Affine::from_uncompressed(&g2.to_uncompressed()).unwrap(),
+        g2
+    );
+    assert_eq!(G1Affine::identity().to_uncompressed(), [0; 64]);
+    assert_eq!(G2Affine::identity().to_uncompressed(), [0; 128]);
+    assert!(G1Affine::from_uncompressed(&[0; 64]).unwrap().is_identity());
+    assert!(G2Affine::from_uncompressed(&[0; 128])
+        .unwrap()
+        .is_identity());
+}
@@ -0,0 +1,109 @@
+//! Known answers for the EIP-2333 tree over the BN254 group order.
+//!
+//! The seeds and child indices are the four test cases of EIP-2333. The
+//! expected keys come from a reference implementation of the EIP that
+//! reproduces its official BLS12-381 answers, run with the BN254 r instead.
+use bn254::{DerivationError, Fr, SecretKey};
+
+/// Seed, child index, master key and child key.
+const VECTORS: [(&str, u32, &str, &str); 4] = [
+    (
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        0,
+        "16876385784863514523309488032647671531381760176997820269052892961094459323096",
+        "6261163673700163178650738809658100478163222593983987165305930523660281595207",
+    ),
+    (
+        "3141592653589793238462643383279502884197169399375105820974944592",
+        3141592653,
+        "229353659466065015837541496932444945060729173326560345507736288250925376270",
+        "2251622119959225382267678512174892219044747370423865091043915542740175118827",
+    ),
+    (
+        "0099ff991111002299dd7744ee3355bbdd8844115566cc55663355668888cc00",
+        4294967295,
+        "18216402388012388254786533226315227966838550967932470819583961139627092135409",
+        "5382347775372111712216110084420056541202794534869398147348620148382560698836",
+    ),
+    (
+        "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        42,
+        "2183211438400025037344214143073701306349415826671090356731890793683104354304",
+        "15693575931479047451749891151725307486820008487903000455863245393333660785642",
+    ),
+];
+
+fn scalar(decimal: &str) -> Fr {
+    Fr::from_str_radix(decimal, 10).unwrap()
+}
+
+#[test]
+fn eip2333_vectors_test() {
+    for (seed, index, master, child) in VECTORS {
+        let seed = hex::decode(seed).unwrap();
+        let master_key = SecretKey::derive_master(&seed).unwrap();
+        assert_eq!(*master_key.expose_secret(), scalar(master));
+        let child_key = master_key.derive_child(index);
+        assert_eq!(*child_key.expose_secret(), scalar(child));
+
+        let path = format!("m/{index}");
+        let derived = SecretKey::derive_path(&seed, &path).unwrap();
+        assert_eq!(derived, child_key);
+    }
+}
+
+#[test]
+fn derive_path_test() {
+    let seed = hex::decode(VECTORS[0].0).unwrap();
+    let expected =
+        scalar("9858384286953664294113346328707988549089656255211929188011147946764474537278");
+    let key = SecretKey::derive_path(&seed, "m/12381/60/0/0").unwrap();
+    assert_eq!(*key.expose_secret(), expected);
+
+    let stepwise = SecretKey::derive_master(&seed)
+        .unwrap()
+        .derive_child(12381)
+        .derive_child(60)
+        .derive_child(0)
+        .derive_child(0);
+    assert_eq!(stepwise, key);
+    assert_eq!(
+        SecretKey::derive_path(&seed, "m").unwrap(),
+        SecretKey::derive_master(&seed).unwrap()
+    );
+
+    for path in [
+        "",
+        "m/",
+        "/0",
+        "n/0",
+        "m0",
+        "m//0",
+        "m/0/",
+        "m/01",
+        "m/+1",
+        "m/-1",
+        "m/0x10",
+        "m/4294967296",
+        "m/12381'/60",
+    ] {
+        assert_eq!(
+            SecretKey::derive_path(&seed, path),
+            Err(DerivationError::InvalidPath),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn derive_master_seed_length_test() {
+    assert_eq!(
+        SecretKey::derive_master(&[0; 31]),
+        Err(DerivationError::SeedTooShort)
+    );
+    assert_eq!(
+        SecretKey::derive_path(&[0; 31], "m/0"),
+        Err(DerivationError::SeedTooShort)
+    );
+    assert!(SecretKey::derive_master(&[0; 32]).is_ok());
+}
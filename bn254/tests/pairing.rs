@@ -91,3 +91,44 @@ fn unitary_test() {
     assert_eq!(p, q);
     assert_eq!(q, r);
 }
+
+#[test]
+fn multi_pairing_test() {
+    let mut rng = OsRng;
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let a = Fr::random(&mut rng);
+    let b = Fr::random(&mut rng);
+
+    let p = G1Affine::from(g1 * a);
+    let q = G2Affine::from(g2 * b);
+    let pq = G1Affine::from(g1 * (a * b));
+
+    // e(aG, bH) * e(-abG, H) = 1
+    assert_eq!(
+        AteParing::multi_pairing(&[(p, q), (-pq, g2)]),
+        Gt::identity()
+    );
+}
+
+#[test]
+fn multi_miller_loop_refs_test() {
+    let mut rng = OsRng;
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let a = G1Affine::from(g1 * Fr::random(&mut rng));
+    let b = G2PairingAffine::from(G2Affine::from(g2 * Fr::random(&mut rng)));
+    let h = G2PairingAffine::from(g2);
+    let identity = G2PairingAffine::from(G2Affine::identity());
+
+    let expected = AteParing::multi_miller_loop(&[(a, b.clone()), (g1, h.clone())]);
+
+    assert_eq!(
+        expected,
+        AteParing::multi_miller_loop_refs(&[(&a, &b), (&g1, &h)])
+    );
+    assert_eq!(
+        Ok(expected),
+        AteParing::try_multi_miller_loop(&[(&a, &b), (&g1, &h), (&g1, &identity)])
+    );
+}
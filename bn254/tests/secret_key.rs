@@ -0,0 +1,73 @@
+use bn254::{
+    FixedBaseTable, Fr, G1Affine, G1Projective, G2Affine, G2Projective, SecretKey, SecretKeyError,
+};
+use core::mem::MaybeUninit;
+use rand_core::OsRng;
+use zeroize::Zeroize;
+
+#[test]
+fn secret_key_encoding_test() {
+    let mut rng = OsRng;
+    for _ in 0..100 {
+        let key = SecretKey::random(&mut rng);
+        assert!(!bool::from(key.expose_secret().is_zero()));
+        let bytes = key.to_bytes();
+        assert_eq!(*bytes, key.expose_secret().to_bytes_be());
+        assert_eq!(SecretKey::from_bytes(&bytes).unwrap(), key);
+    }
+
+    // zero, the modulus and an all ones string are all rejected
+    assert!(bool::from(SecretKey::from_bytes(&[0; 32]).is_none()));
+    let mut modulus = (-Fr::one()).to_bytes_be();
+    modulus[31] += 1;
+    assert!(bool::from(SecretKey::from_bytes(&modulus).is_none()));
+    assert!(bool::from(SecretKey::from_bytes(&[0xff; 32]).is_none()));
+    let mut one = [0; 32];
+    one[31] = 1;
+    assert_eq!(
+        *SecretKey::from_bytes(&one).unwrap().expose_secret(),
+        Fr::one()
+    );
+
+    assert_eq!(SecretKey::try_from(Fr::zero()), Err(SecretKeyError::Zero));
+    let key = SecretKey::try_from(Fr::one()).unwrap();
+    assert_eq!(*key.expose_secret(), Fr::one());
+}
+
+#[test]
+fn secret_key_debug_is_redacted_test() {
+    let key = SecretKey::try_from(Fr::from_u64(0x1234)).unwrap();
+    let debug = format!("{key:?}");
+    assert_eq!(debug, "SecretKey(<redacted>)");
+    assert!(!debug.contains("1234"));
+}
+
+#[test]
+fn secret_key_zeroize_test() {
+    let mut rng = OsRng;
+    let mut key = SecretKey::random(&mut rng);
+    key.zeroize();
+    assert!(bool::from(key.expose_secret().is_zero()));
+
+    // drop in place and read the memory it occupied
+    let mut slot = MaybeUninit::new(SecretKey::random(&mut rng));
+    unsafe { slot.assume_init_drop() };
+    let limbs = unsafe { slot.as_ptr().cast::<[u64; 4]>().read() };
+    assert_eq!(limbs, [0; 4]);
+}
+
+#[test]
+fn secret_key_mul_test() {
+    let mut rng = OsRng;
+    let key = SecretKey::random(&mut rng);
+    let k = *key.expose_secret();
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+
+    assert_eq!(g1 * &key, g1 * k);
+    assert_eq!(G1Projective::from(g1) * &key, g1 * k);
+    assert_eq!(g2 * &key, g2 * k);
+    assert_eq!(G2Projective::from(g2) * &key, g2 * k);
+    assert_eq!(&FixedBaseTable::from(g1) * &key, g1 * k);
+    assert_eq!(&FixedBaseTable::from(g2) * &key, g2 * k);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "serde")]
+
+use bn254::{AteParing, Fr, G1Affine, G2Affine, G2PairingAffine, Gt};
+use rand_core::OsRng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn to_cbor<T: Serialize>(val: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(val, &mut bytes).unwrap();
+    bytes
+}
+
+fn from_cbor<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    ciborium::from_reader(bytes).ok()
+}
+
+/// JSON holds the hex of `encoding`, CBOR its bytes, and both decode back.
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + core::fmt::Debug>(
+    val: T,
+    encoding: &[u8],
+) {
+    let json = serde_json::to_string(&val).unwrap();
+    assert_eq!(json, format!("\"{}\"", hex::encode(encoding)));
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), val);
+    let prefixed = format!("\"0x{}\"", hex::encode(encoding));
+    assert_eq!(serde_json::from_str::<T>(&prefixed).unwrap(), val);
+
+    let cbor = to_cbor(&val);
+    assert!(cbor.ends_with(encoding));
+    assert_eq!(from_cbor::<T>(&cbor).unwrap(), val);
+}
+
+#[test]
+fn fr_serde_test() {
+    let mut rng = OsRng;
+    for _ in 0..20 {
+        let k = Fr::random(&mut rng);
+        round_trip(k, &k.to_bytes_be());
+    }
+
+    // the modulus, and a digit short
+    let modulus = "\"30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001\"";
+    assert!(serde_json::from_str::<Fr>(modulus).is_err());
+    assert!(serde_json::from_str::<Fr>(&modulus[..modulus.len() - 2]).is_err());
+    assert!(serde_json::from_str::<Fr>("\"zz\"").is_err());
+    assert!(from_cbor::<Fr>(&to_cbor(&serde_bytes(&[0xff; 32]))).is_none());
+    assert!(from_cbor::<Fr>(&to_cbor(&serde_bytes(&[0; 31]))).is_none());
+}
+
+#[test]
+fn point_serde_test() {
+    let mut rng = OsRng;
+    round_trip(G1Affine::identity(), &G1Affine::identity().to_compressed());
+    round_trip(G2Affine::identity(), &G2Affine::identity().to_compressed());
+    for _ in 0..10 {
+        let p = G1Affine::from(G1Affine::generator() * Fr::random(&mut rng));
+        round_trip(p, &p.to_compressed());
+        let q = G2Affine::from(G2Affine::generator() * Fr::random(&mut rng));
+        round_trip(q, &q.to_compressed());
+    }
+
+    // flags cleared, so neither a point nor the identity
+    let mut bytes = G1Affine::generator().to_compressed();
+    bytes[0] &= 0x3f;
+    assert!(from_cbor::<G1Affine>(&to_cbor(&serde_bytes(&bytes))).is_none());
+    let json = format!("\"{}\"", hex::encode(bytes));
+    assert!(serde_json::from_str::<G1Affine>(&json).is_err());
+    let mut bytes = G2Affine::generator().to_compressed();
+    bytes[63] ^= 1;
+    assert!(from_cbor::<G2Affine>(&to_cbor(&serde_bytes(&bytes))).is_none());
+}
+
+#[test]
+fn gt_serde_test() {
+    let mut rng = OsRng;
+    round_trip(Gt::identity(), &Gt::identity().to_compressed());
+    let gt = Gt::random(&mut rng);
+    round_trip(gt, &gt.to_compressed());
+
+    let mut bytes = gt.to_compressed();
+    bytes[191] ^= 1;
+    assert!(from_cbor::<Gt>(&to_cbor(&serde_bytes(&bytes))).is_none());
+}
+
+#[test]
+fn g2_prepared_serde_test() {
+    let mut rng = OsRng;
+    let p = G1Affine::from(G1Affine::generator() * Fr::random(&mut rng));
+    let q = G2Affine::from(G2Affine::generator() * Fr::random(&mut rng));
+    let prepared = G2PairingAffine::from(q);
+
+    let json = serde_json::to_string(&prepared).unwrap();
+    assert_eq!(json, serde_json::to_string(&q).unwrap());
+    let decoded: G2PairingAffine = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        AteParing::multi_miller_loop(&[(p, decoded)]),
+        AteParing::multi_miller_loop(&[(p, prepared.clone())])
+    );
+
+    let decoded: G2PairingAffine = from_cbor(&to_cbor(&prepared)).unwrap();
+    assert_eq!(
+        AteParing::multi_miller_loop(&[(p, decoded)]),
+        AteParing::multi_miller_loop(&[(p, prepared)])
+    );
+}
+
+/// A byte string, as serde serializes `&[u8]` as a sequence by default.
+fn serde_bytes(bytes: &[u8]) -> ciborium::Value {
+    ciborium::Value::Bytes(bytes.to_vec())
+}
@@ -0,0 +1,154 @@
+//! dudect-style statistical timing tests.
+//!
+//! Each test times an operation on two classes of inputs, a fixed one and
+//! freshly random ones, interleaved in random order, and runs Welch's t-test
+//! on the two timing distributions. A constant time implementation keeps
+//! |t| small however many samples are taken, while a data dependent one
+//! drives it up as the sample count grows.
+//!
+//! The measurements are only meaningful in an optimized build on a quiet
+//! machine, so the tests are ignored by default:
+//!
+//! cargo test --release --test timing -- --ignored --nocapture
+use bn254::{FixedBaseTable, Fq12, Fr, G1Affine, G2Affine, Gt};
+use rand_core::{OsRng, RngCore};
+use std::hint::black_box;
+use std::time::Instant;
+
+/// dudect treats |t| above 4.5 as evidence of a leak; leave some headroom
+/// for scheduler noise.
+const THRESHOLD: f64 = 10.0;
+const SAMPLES: usize = 4000;
+
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        (self.mean[0] - self.mean[1]) / (var0 / self.n[0] + var1 / self.n[1]).sqrt()
+    }
+}
+
+/// Time `op` on `fixed` against `random` inputs and return the t statistic
+/// after discarding the slowest 5% of samples, which are dominated by
+/// interrupts rather than by the operation.
+fn dudect<T: Copy, O>(fixed: T, random: &[T], op: impl Fn(T) -> O) -> f64 {
+    let mut rng = OsRng;
+    let mut samples = Vec::with_capacity(random.len());
+    for &input in random {
+        let class = (rng.next_u32() & 1) as usize;
+        let input = if class == 0 { fixed } else { input };
+        let start = Instant::now();
+        black_box(op(black_box(input)));
+        samples.push((class, start.elapsed().as_nanos() as f64));
+    }
+
+    let mut sorted = samples.iter().map(|&(_, t)| t).collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cutoff = sorted[sorted.len() * 95 / 100];
+
+    let mut welch = Welch::default();
+    for (class, t) in samples.into_iter().filter(|&(_, t)| t <= cutoff) {
+        welch.push(class, t);
+    }
+    welch.t()
+}
+
+#[test]
+#[ignore]
+fn g1_scalar_mul_is_constant_time() {
+    let mut rng = OsRng;
+    let g = G1Affine::generator();
+    let random = (0..SAMPLES)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+
+    let t = dudect(Fr::one(), &random, |k| g * k);
+    println!("g1 scalar mul: t = {t:.2}");
+    assert!(t.abs() < THRESHOLD);
+}
+
+#[test]
+#[ignore]
+fn g2_scalar_mul_is_constant_time() {
+    let mut rng = OsRng;
+    let g = G2Affine::generator();
+    let random = (0..SAMPLES / 4)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+
+    let t = dudect(Fr::zero(), &random, |k| g * k);
+    println!("g2 scalar mul: t = {t:.2}");
+    assert!(t.abs() < THRESHOLD);
+}
+
+#[test]
+#[ignore]
+fn g1_fixed_base_mul_is_constant_time() {
+    let mut rng = OsRng;
+    let table = FixedBaseTable::from(G1Affine::generator());
+    let random = (0..SAMPLES)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+
+    let t = dudect(Fr::zero(), &random, |k| &table * k);
+    println!("g1 fixed-base mul: t = {t:.2}");
+    assert!(t.abs() < THRESHOLD);
+}
+
+#[test]
+#[ignore]
+fn fq12_invert_is_constant_time() {
+    // consecutive squares of the Gt generator are cheap pseudo random inputs
+    let mut f = Gt::generator().0;
+    let random = (0..SAMPLES)
+        .map(|_| {
+            f = f.square();
+            f
+        })
+        .collect::<Vec<_>>();
+
+    let t = dudect(Fq12::one(), &random, |f| f.invert());
+    println!("fq12 invert: t = {t:.2}");
+    assert!(t.abs() < THRESHOLD);
+}
+
+#[test]
+#[ignore]
+fn fr_invert_is_constant_time() {
+    let mut rng = OsRng;
+    let random = (0..SAMPLES)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+
+    let t = dudect(Fr::one(), &random, |a| a.invert());
+    println!("fr invert: t = {t:.2}");
+    assert!(t.abs() < THRESHOLD);
+}
+
+#[test]
+#[ignore]
+fn gt_scalar_mul_is_constant_time() {
+    let mut rng = OsRng;
+    let g = Gt::generator();
+    let random = (0..SAMPLES / 4)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+
+    let t = dudect(Fr::one(), &random, |k| g * k);
+    println!("gt scalar mul: t = {t:.2}");
+    assert!(t.abs() < THRESHOLD);
+}
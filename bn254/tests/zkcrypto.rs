@@ -0,0 +1,133 @@
+#![cfg(feature = "zkcrypto")]
+
+use bn254::{Bn254, Fq, Fr, G1Affine, G1Projective, G2Affine, G2PairingAffine, G2Projective, Gt};
+use ff::{Field, FromUniformBytes, PrimeField, WithSmallOrderMulGroup};
+use group::Group;
+use pairing::{Engine, MillerLoopResult, MultiMillerLoop};
+use rand_core::{OsRng, RngCore};
+
+/// The constants `ff` derives its square roots and FFT domains from.
+fn prime_field_constants<F: PrimeField + WithSmallOrderMulGroup<3>>() {
+    assert_eq!(F::TWO_INV.double(), F::ONE);
+    assert_eq!(F::ROOT_OF_UNITY * F::ROOT_OF_UNITY_INV, F::ONE);
+    let mut root = F::ROOT_OF_UNITY;
+    for _ in 1..F::S {
+        root = root.square();
+        assert_ne!(root, F::ONE);
+    }
+    assert_eq!(root.square(), F::ONE);
+    assert_eq!(F::DELTA, F::MULTIPLICATIVE_GENERATOR.pow([1 << F::S]));
+    // a generator is a quadratic non-residue
+    assert!(bool::from(F::MULTIPLICATIVE_GENERATOR.sqrt().is_none()));
+    assert_ne!(F::ZETA, F::ONE);
+    assert_eq!(F::ZETA.cube(), F::ONE);
+
+    let mut rng = OsRng;
+    for _ in 0..100 {
+        let a = F::random(&mut rng);
+        assert_eq!(F::from_repr(a.to_repr()).unwrap(), a);
+        assert_eq!(a.is_odd().unwrap_u8(), a.to_repr().as_ref()[0] & 1);
+        let (is_square, root) = F::sqrt_ratio(&a.square(), &F::ONE);
+        assert!(bool::from(is_square));
+        assert_eq!(root.square(), a.square());
+        assert_eq!([a, a, a].iter().sum::<F>(), a + a + a);
+        assert_eq!([a, a, a].iter().product::<F>(), a.cube());
+    }
+    assert_eq!(F::from(7) * F::from(6), F::from(42));
+}
+
+#[test]
+fn fr_prime_field_test() {
+    prime_field_constants::<Fr>();
+    // the modulus itself is the first integer rejected
+    assert!(Fr::from_str_radix(&Fr::MODULUS[2..], 16).is_none());
+}
+
+#[test]
+fn fq_prime_field_test() {
+    prime_field_constants::<Fq>();
+    assert!(Fq::from_str_radix(&Fq::MODULUS[2..], 16).is_none());
+}
+
+#[test]
+fn fr_from_uniform_bytes_test() {
+    let mut rng = OsRng;
+    assert_eq!(Fr::from_uniform_bytes(&[0; 64]), Fr::zero());
+    let mut one = [0; 64];
+    one[0] = 1;
+    assert_eq!(Fr::from_uniform_bytes(&one), Fr::one());
+    // 2^256 * 2^248
+    let mut high = [0; 64];
+    high[63] = 1;
+    let two_256 = Fr::from_u64(2).pow_vartime([256]);
+    let two_248 = Fr::from_u64(2).pow_vartime([248]);
+    assert_eq!(Fr::from_uniform_bytes(&high), two_256 * two_248);
+
+    for _ in 0..100 {
+        let mut bytes = [0; 64];
+        rng.fill_bytes(&mut bytes);
+        let lo = Fr::from_uniform_bytes(&{
+            let mut b = [0; 64];
+            b[..32].copy_from_slice(&bytes[..32]);
+            b
+        });
+        let hi = Fr::from_uniform_bytes(&{
+            let mut b = [0; 64];
+            b[..32].copy_from_slice(&bytes[32..]);
+            b
+        });
+        assert_eq!(Fr::from_uniform_bytes(&bytes), lo + hi * two_256);
+    }
+}
+
+#[test]
+fn g1_curve_test() {
+    group::tests::curve_tests::<G1Projective>();
+    group::tests::random_uncompressed_encoding_tests::<G1Projective>();
+}
+
+#[test]
+fn g2_curve_test() {
+    group::tests::curve_tests::<G2Projective>();
+    group::tests::random_uncompressed_encoding_tests::<G2Projective>();
+}
+
+/// Bilinearity through the `Engine` and `MultiMillerLoop` traits alone.
+fn engine_bilinearity<E: MultiMillerLoop>() {
+    let mut rng = OsRng;
+    let a = E::Fr::random(&mut rng);
+    let b = E::Fr::random(&mut rng);
+    let p: E::G1Affine = (E::G1::generator() * a).into();
+    let q: E::G2Affine = (E::G2::generator() * b).into();
+    let g1: E::G1Affine = E::G1::generator().into();
+    let g2: E::G2Affine = E::G2::generator().into();
+
+    let e = E::pairing(&p, &q);
+    assert_eq!(e, E::pairing(&g1, &g2) * (a * b));
+    assert_eq!(e, E::Gt::generator() * (a * b));
+
+    let q_prepared = E::G2Prepared::from(q);
+    let neg_prepared = E::G2Prepared::from((-(E::G2::generator() * b)).into());
+    let res = E::multi_miller_loop(&[(&p, &q_prepared), (&p, &neg_prepared)]);
+    assert!(bool::from(res.final_exponentiation().is_identity()));
+    assert_eq!(
+        E::multi_miller_loop(&[(&p, &q_prepared)]).final_exponentiation(),
+        e
+    );
+}
+
+#[test]
+fn bn254_engine_test() {
+    engine_bilinearity::<Bn254>();
+
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let prepared = G2PairingAffine::from(g2);
+    let res = Bn254::multi_miller_loop(&[(&g1, &prepared)])
+        + Bn254::multi_miller_loop(&[(&g1, &prepared)]);
+    assert_eq!(
+        res.final_exponentiation(),
+        Gt::generator() + Gt::generator()
+    );
+    assert_eq!(Bn254::pairing(&g1, &g2), Gt::generator());
+}